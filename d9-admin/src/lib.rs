@@ -0,0 +1,164 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//! the pallet admin role shared by the d9 pallets
+//!
+//! each pallet keeps its admin and any pending successor in its own storage values and hands them
+//! to `AdminHandover`: root sets the admin at once, while the admin only proposes a successor, who
+//! has to accept the role once the pallet's timelock has passed. until then root or the admin may
+//! cancel the proposal.
+//!
+//! each pallet's `AdminTransferDelay` is the timelock, the blocks a proposed admin waits before it
+//! may accept. zero lets it accept at once.
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
+use frame_support::{ensure, traits::EnsureOrigin, RuntimeDebug, StorageValue};
+use frame_system::RawOrigin;
+use scale_info::TypeInfo;
+use sp_runtime::{traits::BadOrigin, DispatchError, Saturating};
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests;
+
+/// a successor proposed by the admin
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct PendingAdmin<AccountId, BlockNumber> {
+    pub proposed: AccountId,
+    /// the first block at which `proposed` may accept
+    pub acceptable_at: BlockNumber,
+}
+
+/// why a handover step was refused
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AdminError {
+    /// the caller is neither root nor the admin
+    NotAdmin,
+    NoPendingAdmin,
+    /// the caller is not the proposed successor
+    NotPendingAdmin,
+    /// the proposed successor may not accept yet
+    TimelockActive,
+}
+
+/// the errors a pallet reports for refused handover steps
+pub trait AdminErrors: Into<DispatchError> {
+    const NO_PENDING_ADMIN: Self;
+    const NOT_PENDING_ADMIN: Self;
+    const TIMELOCK_ACTIVE: Self;
+}
+
+impl AdminError {
+    /// this refusal as an error of the pallet whose errors are `E`, `BadOrigin` for `NotAdmin`
+    pub fn into_dispatch_error<E: AdminErrors>(self) -> DispatchError {
+        match self {
+            AdminError::NotAdmin => BadOrigin.into(),
+            AdminError::NoPendingAdmin => E::NO_PENDING_ADMIN.into(),
+            AdminError::NotPendingAdmin => E::NOT_PENDING_ADMIN.into(),
+            AdminError::TimelockActive => E::TIMELOCK_ACTIVE.into(),
+        }
+    }
+}
+
+/// the outcome of a handover step
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum AdminChange<AccountId, BlockNumber> {
+    /// (proposed admin, first block it may accept)
+    Proposed(AccountId, BlockNumber),
+    /// (old admin, new admin)
+    Changed(Option<AccountId>, AccountId),
+}
+
+/// handover rules over a pallet's `Admin` and `Pending` storage values
+pub struct AdminHandover<AccountId, BlockNumber, Admin, Pending>(
+    PhantomData<(AccountId, BlockNumber, Admin, Pending)>,
+);
+impl<AccountId, BlockNumber, Admin, Pending> AdminHandover<AccountId, BlockNumber, Admin, Pending>
+where
+    AccountId: FullCodec + Clone + PartialEq,
+    BlockNumber: FullCodec + Clone + PartialOrd + Saturating,
+    Admin: StorageValue<AccountId, Query = Option<AccountId>>,
+    Pending: StorageValue<
+        PendingAdmin<AccountId, BlockNumber>,
+        Query = Option<PendingAdmin<AccountId, BlockNumber>>,
+    >,
+{
+    pub fn is_admin(who: &AccountId) -> bool {
+        Admin::get().as_ref() == Some(who)
+    }
+
+    /// hand the admin role to `new_admin`
+    ///
+    /// root, a `caller` of `None`, sets the admin at once and drops any pending successor. the
+    /// admin proposes `new_admin`, who may accept `delay` blocks after `now`. a new proposal
+    /// replaces the pending one and restarts the timelock, even for the same `new_admin`.
+    pub fn propose(
+        caller: Option<AccountId>,
+        new_admin: AccountId,
+        now: BlockNumber,
+        delay: BlockNumber,
+    ) -> Result<AdminChange<AccountId, BlockNumber>, AdminError> {
+        match caller {
+            None => {
+                Pending::kill();
+                let old_admin = Admin::get();
+                Admin::put(new_admin.clone());
+                Ok(AdminChange::Changed(old_admin, new_admin))
+            }
+            Some(caller) => {
+                ensure!(Self::is_admin(&caller), AdminError::NotAdmin);
+                let acceptable_at = now.saturating_add(delay);
+                Pending::put(PendingAdmin {
+                    proposed: new_admin.clone(),
+                    acceptable_at: acceptable_at.clone(),
+                });
+                Ok(AdminChange::Proposed(new_admin, acceptable_at))
+            }
+        }
+    }
+
+    /// drop the pending successor, returning it
+    ///
+    /// root, a `caller` of `None`, or the admin may cancel.
+    pub fn cancel(caller: Option<AccountId>) -> Result<AccountId, AdminError> {
+        if let Some(caller) = caller {
+            ensure!(Self::is_admin(&caller), AdminError::NotAdmin);
+        }
+        let pending = Pending::take().ok_or(AdminError::NoPendingAdmin)?;
+        Ok(pending.proposed)
+    }
+
+    /// `who` takes over the admin role proposed to it, returning the old admin
+    pub fn accept(who: AccountId, now: BlockNumber) -> Result<Option<AccountId>, AdminError> {
+        let pending = Pending::get().ok_or(AdminError::NoPendingAdmin)?;
+        ensure!(pending.proposed == who, AdminError::NotPendingAdmin);
+        ensure!(now >= pending.acceptable_at, AdminError::TimelockActive);
+        Pending::kill();
+        let old_admin = Admin::get();
+        Admin::put(who);
+        Ok(old_admin)
+    }
+}
+
+/// root, or the account held in `Admin`
+///
+/// succeeds with `None` for root and with the admin's account otherwise.
+pub struct EnsureRootOrAdmin<AccountId, Admin>(PhantomData<(AccountId, Admin)>);
+impl<O, AccountId, Admin> EnsureOrigin<O> for EnsureRootOrAdmin<AccountId, Admin>
+where
+    O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+    AccountId: FullCodec + PartialEq,
+    Admin: StorageValue<AccountId, Query = Option<AccountId>>,
+{
+    type Success = Option<AccountId>;
+
+    fn try_origin(o: O) -> Result<Self::Success, O> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Root => Ok(None),
+            RawOrigin::Signed(who) if Admin::get().as_ref() == Some(&who) => Ok(Some(who)),
+            o => Err(O::from(o)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn try_successful_origin() -> Result<O, ()> {
+        Ok(O::from(RawOrigin::Root))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::*;
+use frame_support::storage_alias;
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+
+pub const ADMIN: AccountId = 1;
+pub const SUCCESSOR: AccountId = 2;
+pub const OTHER: AccountId = 3;
+pub const DELAY: BlockNumber = 10;
+
+#[storage_alias]
+pub type Admin = StorageValue<AdminTest, AccountId>;
+#[storage_alias]
+pub type Pending = StorageValue<AdminTest, PendingAdmin<AccountId, BlockNumber>>;
+
+pub type Handover = AdminHandover<AccountId, BlockNumber, Admin, Pending>;
+
+/// stands in for a pallet's `Error`
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TestError {
+    NoPendingAdmin,
+    NotPendingAdmin,
+    AdminTimelockActive,
+}
+
+impl From<TestError> for DispatchError {
+    fn from(error: TestError) -> Self {
+        DispatchError::Other(match error {
+            TestError::NoPendingAdmin => "NoPendingAdmin",
+            TestError::NotPendingAdmin => "NotPendingAdmin",
+            TestError::AdminTimelockActive => "AdminTimelockActive",
+        })
+    }
+}
+
+impl AdminErrors for TestError {
+    const NO_PENDING_ADMIN: Self = TestError::NoPendingAdmin;
+    const NOT_PENDING_ADMIN: Self = TestError::NotPendingAdmin;
+    const TIMELOCK_ACTIVE: Self = TestError::AdminTimelockActive;
+}
+
+/// ADMIN holds the role and nothing is pending
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext = sp_io::TestExternalities::default();
+    ext.execute_with(|| Admin::put(ADMIN));
+    ext
+}
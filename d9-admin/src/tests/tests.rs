@@ -0,0 +1,125 @@
+use super::mock::*;
+use crate::*;
+use frame_support::assert_noop;
+
+fn pending(
+    proposed: AccountId,
+    acceptable_at: BlockNumber,
+) -> Option<PendingAdmin<AccountId, BlockNumber>> {
+    Some(PendingAdmin {
+        proposed,
+        acceptable_at,
+    })
+}
+
+#[test]
+fn the_admin_proposes_and_the_successor_accepts_after_the_delay() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY),
+            Ok(AdminChange::Proposed(SUCCESSOR, 15))
+        );
+        assert_eq!(Admin::get(), Some(ADMIN));
+        assert_eq!(Pending::get(), pending(SUCCESSOR, 15));
+        assert_noop!(Handover::accept(SUCCESSOR, 14), AdminError::TimelockActive);
+        assert_eq!(Handover::accept(SUCCESSOR, 15), Ok(Some(ADMIN)));
+        assert!(Handover::is_admin(&SUCCESSOR));
+        assert_eq!(Pending::get(), None);
+    });
+}
+
+#[test]
+fn a_zero_delay_may_be_accepted_at_once() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            Handover::propose(Some(ADMIN), SUCCESSOR, 5, 0),
+            Ok(AdminChange::Proposed(SUCCESSOR, 5))
+        );
+        assert_eq!(Handover::accept(SUCCESSOR, 5), Ok(Some(ADMIN)));
+    });
+}
+
+#[test]
+fn only_the_admin_may_propose() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Handover::propose(Some(OTHER), OTHER, 5, DELAY),
+            AdminError::NotAdmin
+        );
+    });
+}
+
+#[test]
+fn only_the_proposed_successor_may_accept() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Handover::accept(SUCCESSOR, 20), AdminError::NoPendingAdmin);
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY).is_ok());
+        assert_noop!(Handover::accept(OTHER, 20), AdminError::NotPendingAdmin);
+        assert_noop!(Handover::accept(ADMIN, 20), AdminError::NotPendingAdmin);
+        assert_eq!(Pending::get(), pending(SUCCESSOR, 15));
+    });
+}
+
+#[test]
+fn root_sets_the_admin_at_once_and_drops_the_proposal() {
+    new_test_ext().execute_with(|| {
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY).is_ok());
+        assert_eq!(
+            Handover::propose(None, OTHER, 6, DELAY),
+            Ok(AdminChange::Changed(Some(ADMIN), OTHER))
+        );
+        assert_eq!(Admin::get(), Some(OTHER));
+        assert_eq!(Pending::get(), None);
+        assert_noop!(Handover::accept(SUCCESSOR, 20), AdminError::NoPendingAdmin);
+    });
+}
+
+#[test]
+fn proposing_again_replaces_the_proposal_and_restarts_the_timelock() {
+    new_test_ext().execute_with(|| {
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY).is_ok());
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 8, DELAY).is_ok());
+        assert_eq!(Pending::get(), pending(SUCCESSOR, 18));
+        assert_noop!(Handover::accept(SUCCESSOR, 15), AdminError::TimelockActive);
+        assert!(Handover::propose(Some(ADMIN), OTHER, 9, DELAY).is_ok());
+        assert_noop!(Handover::accept(SUCCESSOR, 20), AdminError::NotPendingAdmin);
+        assert_eq!(Handover::accept(OTHER, 19), Ok(Some(ADMIN)));
+    });
+}
+
+#[test]
+fn root_or_the_admin_may_cancel_a_proposal() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Handover::cancel(Some(ADMIN)), AdminError::NoPendingAdmin);
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY).is_ok());
+        assert_noop!(Handover::cancel(Some(SUCCESSOR)), AdminError::NotAdmin);
+        assert_eq!(Handover::cancel(Some(ADMIN)), Ok(SUCCESSOR));
+        assert_eq!(Pending::get(), None);
+        assert_noop!(Handover::accept(SUCCESSOR, 20), AdminError::NoPendingAdmin);
+
+        assert!(Handover::propose(Some(ADMIN), SUCCESSOR, 5, DELAY).is_ok());
+        assert_eq!(Handover::cancel(None), Ok(SUCCESSOR));
+        assert_eq!(Admin::get(), Some(ADMIN));
+        assert_eq!(Pending::get(), None);
+    });
+}
+
+#[test]
+fn refusals_become_the_pallets_own_errors() {
+    assert_eq!(
+        AdminError::NotAdmin.into_dispatch_error::<TestError>(),
+        DispatchError::BadOrigin
+    );
+    assert_eq!(
+        AdminError::NoPendingAdmin.into_dispatch_error::<TestError>(),
+        TestError::NoPendingAdmin.into()
+    );
+    assert_eq!(
+        AdminError::NotPendingAdmin.into_dispatch_error::<TestError>(),
+        TestError::NotPendingAdmin.into()
+    );
+    assert_eq!(
+        AdminError::TimelockActive.into_dispatch_error::<TestError>(),
+        TestError::AdminTimelockActive.into()
+    );
+}
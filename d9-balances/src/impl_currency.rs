@@ -287,7 +287,7 @@ where
         if amount.is_zero() {
             return Ok(());
         }
-        if Locks::<T, I>::contains_key(who) {
+        if Self::is_frozen_by_lock(who) {
             return Err(Error::<T, I>::LiquidityRestrictions.into());
         }
         ensure!(
@@ -401,7 +401,8 @@ where
             return Self::PositiveImbalance::zero();
         }
 
-        Self::try_mutate_account_handling_dust(
+        let mut endowed = false;
+        let imbalance = Self::try_mutate_account_handling_dust(
             who,
             |account, is_new| -> Result<Self::PositiveImbalance, DispatchError> {
                 let ed = T::ExistentialDeposit::get();
@@ -418,10 +419,15 @@ where
                     who: who.clone(),
                     amount: value,
                 });
+                endowed = is_new;
                 Ok(PositiveImbalance::new(value))
             },
         )
-        .unwrap_or_else(|_| Self::PositiveImbalance::zero())
+        .unwrap_or_else(|_| Self::PositiveImbalance::zero());
+        if endowed {
+            Self::note_referral(None, who, true);
+        }
+        imbalance
     }
 
     /// Withdraw some free balance from an account, respecting existence requirements.
@@ -19,8 +19,10 @@
 use super::*;
 use frame_support::traits::tokens::{
 	Fortitude,
+	Precision::{self, BestEffort},
 	Preservation::{self, Preserve, Protect},
-	Provenance::{self, Minted},
+	Provenance::{self, Extant, Minted},
+	Restriction,
 };
 
 impl<T: Config<I>, I: 'static> fungible::Inspect<T::AccountId> for Pallet<T, I> {
@@ -183,6 +185,42 @@ impl<T: Config<I>, I: 'static> fungible::Unbalanced<T::AccountId> for Pallet<T,
 }
 
 impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
+	/// Same as the default `fungible::Mutate::transfer`, but every transfer is first checked
+	/// against the transfer deny list and `Config::TransferFilter`.
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		preservation: Preservation,
+	) -> Result<Self::Balance, DispatchError> {
+		Self::ensure_transfer_allowed(source, dest, amount)?;
+		let endowed = <Self as fungible::Inspect<_>>::total_balance(dest).is_zero();
+		let _extra = <Self as fungible::Inspect<_>>::can_withdraw(source, amount)
+			.into_result(preservation != Expendable)?;
+		<Self as fungible::Inspect<_>>::can_deposit(dest, amount, Extant).into_result()?;
+		// Only a transfer that can go through counts against the limit. Charged before the
+		// balances move, as callers outside an extrinsic get no rollback if it fails.
+		Self::charge_transfer_limit(source, amount, Polite)?;
+		<Self as fungible::Unbalanced<_>>::decrease_balance(
+			source,
+			amount,
+			BestEffort,
+			preservation,
+			Polite,
+		)?;
+		// This should never fail as we checked `can_deposit` earlier. But we do a best-effort
+		// revert in case it does.
+		if let Err(e) = <Self as fungible::Unbalanced<_>>::increase_balance(dest, amount, BestEffort)
+		{
+			let _ = <Self as fungible::Unbalanced<_>>::increase_balance(source, amount, BestEffort)
+				.defensive();
+			return Err(e)
+		}
+		<Self as fungible::Mutate<_>>::done_transfer(source, dest, amount);
+		Self::note_referral(Some(source), dest, endowed);
+		Ok(amount)
+	}
+
 	fn done_mint_into(who: &T::AccountId, amount: Self::Balance) {
 		Self::deposit_event(Event::<T, I>::Minted { who: who.clone(), amount });
 	}
@@ -204,7 +242,59 @@ impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
 	}
 }
 
-impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {}
+impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {
+	/// Same as the default `fungible::MutateHold::transfer_on_hold`, but the transfer is first
+	/// checked against the transfer deny list and `Config::TransferFilter`, and counts against the
+	/// source's transfer limit. A `Force` transfer is counted without being held to the limit.
+	fn transfer_on_hold(
+		reason: &Self::Reason,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		mut amount: Self::Balance,
+		precision: Precision,
+		mode: Restriction,
+		force: Fortitude,
+	) -> Result<Self::Balance, DispatchError> {
+		Self::ensure_transfer_allowed(source, dest, amount)?;
+		// We must check total-balance requirements if `!force`.
+		let have = <Self as fungible::InspectHold<_>>::balance_on_hold(reason, source);
+		let liquid =
+			<Self as fungible::InspectHold<_>>::reducible_total_balance_on_hold(source, force);
+		if let BestEffort = precision {
+			amount = amount.min(liquid).min(have);
+		} else {
+			ensure!(amount <= liquid, TokenError::Frozen);
+			ensure!(amount <= have, TokenError::FundsUnavailable);
+		}
+
+		// We want to make sure we can deposit the amount in advance. If we can't then something is
+		// very wrong.
+		ensure!(
+			<Self as fungible::Inspect<_>>::can_deposit(dest, amount, Extant) ==
+				DepositConsequence::Success,
+			TokenError::CannotCreate
+		);
+		ensure!(
+			mode == Restriction::Free ||
+				<Self as fungible::InspectHold<_>>::hold_available(reason, dest),
+			TokenError::CannotCreateHold
+		);
+		Self::charge_transfer_limit(source, amount, force)?;
+
+		let amount = <Self as fungible::UnbalancedHold<_>>::decrease_balance_on_hold(
+			reason, source, amount, precision,
+		)?;
+		let actual = if mode == Restriction::OnHold {
+			<Self as fungible::UnbalancedHold<_>>::increase_balance_on_hold(
+				reason, dest, amount, precision,
+			)?
+		} else {
+			<Self as fungible::Unbalanced<_>>::increase_balance(dest, amount, precision)?
+		};
+		<Self as fungible::MutateHold<_>>::done_transfer_on_hold(reason, source, dest, actual);
+		Ok(actual)
+	}
+}
 
 impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
 	type Reason = T::HoldIdentifier;
@@ -161,6 +161,8 @@ mod benchmarking;
 mod impl_currency;
 mod impl_fungible;
 pub mod migration;
+#[cfg(test)]
+mod tests;
 mod types;
 pub mod weights;
 use codec::{Codec, MaxEncodedLen};
@@ -176,7 +178,7 @@ use frame_support::{
             Preservation::{Expendable, Preserve, Protect},
             WithdrawConsequence,
         },
-        Currency, Defensive, Get, OnUnbalanced, ReservableCurrency, StoredMap,
+        Currency, Defensive, Get, OnKilledAccount, OnUnbalanced, ReservableCurrency, StoredMap,
     },
     BoundedSlice, WeakBoundedVec,
 };
@@ -191,7 +193,10 @@ use sp_runtime::{
     ArithmeticError, DispatchError, FixedPointOperand, Perbill, RuntimeDebug, TokenError,
 };
 use sp_std::{cmp, fmt::Debug, mem, prelude::*, result};
-pub use types::{AccountData, BalanceLock, DustCleaner, IdAmount, Reasons, ReserveData};
+pub use types::{
+    AccountData, BalanceLock, DustCleaner, IdAmount, PendingTransferLimit, Reasons, ReserveData,
+    TransferBucket, TransferLimit,
+};
 pub use weights::WeightInfo;
 
 pub use pallet::*;
@@ -205,7 +210,7 @@ pub mod pallet {
     use super::*;
     use frame_support::{
         pallet_prelude::*,
-        traits::{fungible::Credit, tokens::Precision},
+        traits::{fungible::Credit, tokens::Precision, Contains, LockIdentifier},
     };
     use frame_system::pallet_prelude::*;
     pub type CreditOf<T, I> = Credit<<T as frame_system::Config>::AccountId, Pallet<T, I>>;
@@ -279,6 +284,34 @@ pub mod pallet {
 
         /// referral pallet
         type ReferralManager: ReferralManager<Self, I>;
+
+        /// Decides the referral parent of accounts receiving funds from any entry point.
+        type ReferralPolicy: ReferralPolicy<Self::AccountId>;
+
+        /// Notified whenever this pallet reaps an account.
+        ///
+        /// Only called once the system account is gone as well; an account kept alive by other
+        /// providers or sufficients is not reported.
+        type OnAccountReaped: OnKilledAccount<Self::AccountId>;
+
+        /// Lock ids that freeze the whole free balance, blocking every transfer and withdrawal.
+        /// Any other lock only keeps its own amount in place. `Everything` freezes on any lock.
+        type AccountFreezingLocks: Contains<LockIdentifier>;
+
+        /// Hook consulted on every transfer between two accounts. Use `()` to allow everything.
+        type TransferFilter: TransferFilter<Self::AccountId, Self::Balance>;
+
+        /// Origin allowed to manage the transfer deny and allow lists.
+        type TransferListOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Number of blocks a raised or removed transfer limit waits before it applies.
+        #[pallet::constant]
+        type TransferLimitIncreaseDelay: Get<Self::BlockNumber>;
+
+        /// Number of sub-periods a transfer limit window is tracked in. More buckets follow the
+        /// rolling window more closely at the cost of a larger usage entry per account.
+        #[pallet::constant]
+        type TransferLimitBuckets: Get<u32>;
     }
 
     /// The current storage version.
@@ -390,6 +423,31 @@ pub mod pallet {
             who: T::AccountId,
             amount: T::Balance,
         },
+        /// A transfer was rejected by the deny list or the transfer filter.
+        TransferBlocked {
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: T::Balance,
+        },
+        /// An account was added to the transfer deny list.
+        AddedToTransferDenyList { who: T::AccountId },
+        /// An account was removed from the transfer deny list.
+        RemovedFromTransferDenyList { who: T::AccountId },
+        /// An account was added to the transfer allow list.
+        AddedToTransferAllowList { who: T::AccountId },
+        /// An account was removed from the transfer allow list.
+        RemovedFromTransferAllowList { who: T::AccountId },
+        /// An outbound transfer limit now applies to an account. `None` means no limit.
+        TransferLimitSet {
+            who: T::AccountId,
+            limit: Option<TransferLimit<T::Balance, T::BlockNumber>>,
+        },
+        /// A raised or removed transfer limit was scheduled.
+        TransferLimitChangeScheduled {
+            who: T::AccountId,
+            limit: Option<TransferLimit<T::Balance, T::BlockNumber>>,
+            effective_at: T::BlockNumber,
+        },
     }
 
     #[pallet::error]
@@ -414,6 +472,14 @@ pub mod pallet {
         TooManyHolds,
         /// Number of freezes exceed `MaxFreezes`.
         TooManyFreezes,
+        /// Transfer rejected by the deny list or the transfer filter.
+        TransferBlocked,
+        /// Transfer would exceed the sender's outbound transfer limit.
+        TransferLimitExceeded,
+        /// A transfer limit window must be at least one block.
+        InvalidTransferLimit,
+        /// The account has no transfer limit.
+        NoTransferLimit,
     }
 
     /// The total units issued in the system.
@@ -500,6 +566,50 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Accounts that may neither send nor receive transfers.
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_deny_list)]
+    pub type TransferDenyList<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Accounts whose transfers skip `Config::TransferFilter`. The deny list still applies.
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_allow_list)]
+    pub type TransferAllowList<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Outbound transfer limits chosen by account holders.
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_limits)]
+    pub type TransferLimits<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        TransferLimit<T::Balance, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Raised or removed transfer limits that have not yet taken effect.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_transfer_limits)]
+    pub type PendingTransferLimits<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        PendingTransferLimit<T::Balance, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Outbound volume of limited accounts over their rolling window, oldest bucket first.
+    #[pallet::storage]
+    pub type TransferLimitUsage<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<TransferBucket<T::Balance, T::BlockNumber>, T::TransferLimitBuckets>,
+        ValueQuery,
+    >;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
         pub balances: Vec<(T::AccountId, T::Balance)>,
@@ -577,12 +687,16 @@ pub mod pallet {
 
     #[pallet::hooks]
     impl<T: Config<I>, I: 'static> Hooks<T::BlockNumber> for Pallet<T, I> {
-        #[cfg(not(feature = "insecure_zero_ed"))]
         fn integrity_test() {
+            #[cfg(not(feature = "insecure_zero_ed"))]
             assert!(
                 !<T as Config<I>>::ExistentialDeposit::get().is_zero(),
                 "The existential deposit must be greater than zero!"
             );
+            assert!(
+                T::TransferLimitBuckets::get() > 0,
+                "Transfer limits need at least one bucket!"
+            );
         }
     }
 
@@ -596,6 +710,9 @@ pub mod pallet {
         ///
         /// The dispatch origin for this call must be `Signed` by the transactor.
         #[pallet::call_index(0)]
+        #[pallet::weight(
+            T::WeightInfo::transfer_allow_death().saturating_add(Pallet::<T, I>::transfer_checks_weight())
+        )]
         pub fn transfer_allow_death(
             origin: OriginFor<T>,
             dest: AccountIdLookupOf<T>,
@@ -603,9 +720,6 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let source = ensure_signed(origin)?;
             let dest = T::Lookup::lookup(dest)?;
-            if T::ReferralManager::get_parent(&dest).is_none() {
-                T::ReferralManager::create_referral_relationship(&source, &dest);
-            }
             Self::ensure_unlocked(&source)?;
             <Self as fungible::Mutate<_>>::transfer(&source, &dest, value, Expendable)?;
             Ok(().into())
@@ -664,6 +778,9 @@ pub mod pallet {
         /// Exactly as `transfer_allow_death`, except the origin must be root and the source account
         /// may be specified.
         #[pallet::call_index(2)]
+        #[pallet::weight(
+            T::WeightInfo::force_transfer().saturating_add(Pallet::<T, I>::transfer_checks_weight())
+        )]
         pub fn force_transfer(
             origin: OriginFor<T>,
             source: AccountIdLookupOf<T>,
@@ -685,6 +802,9 @@ pub mod pallet {
         ///
         /// [`transfer_allow_death`]: struct.Pallet.html#method.transfer
         #[pallet::call_index(3)]
+        #[pallet::weight(
+            T::WeightInfo::transfer_keep_alive().saturating_add(Pallet::<T, I>::transfer_checks_weight())
+        )]
         pub fn transfer_keep_alive(
             origin: OriginFor<T>,
             dest: AccountIdLookupOf<T>,
@@ -692,9 +812,6 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let source = ensure_signed(origin)?;
             let dest = T::Lookup::lookup(dest)?;
-            if T::ReferralManager::get_parent(&dest).is_none() {
-                T::ReferralManager::create_referral_relationship(&source, &dest);
-            }
             Self::ensure_unlocked(&source)?;
             <Self as fungible::Mutate<_>>::transfer(&source, &dest, value, Preserve)?;
             Ok(().into())
@@ -716,6 +833,9 @@ pub mod pallet {
         ///   transfer everything except at least the existential deposit, which will guarantee to
         ///   keep the sender account alive (true).
         #[pallet::call_index(4)]
+        #[pallet::weight(
+            T::WeightInfo::transfer_all().saturating_add(Pallet::<T, I>::transfer_checks_weight())
+        )]
         pub fn transfer_all(
             origin: OriginFor<T>,
             dest: AccountIdLookupOf<T>,
@@ -791,7 +911,9 @@ pub mod pallet {
         ///
         /// WARNING: DEPRECATED! Will be released in approximately 3 months.
         #[pallet::call_index(7)]
-        #[pallet::weight(T::WeightInfo::transfer_allow_death())]
+        #[pallet::weight(
+            T::WeightInfo::transfer_allow_death().saturating_add(Pallet::<T, I>::transfer_checks_weight())
+        )]
         pub fn transfer(
             origin: OriginFor<T>,
             dest: AccountIdLookupOf<T>,
@@ -800,9 +922,6 @@ pub mod pallet {
             let source = ensure_signed(origin)?;
             let dest = T::Lookup::lookup(dest)?;
             Self::ensure_unlocked(&source)?;
-            if T::ReferralManager::get_parent(&dest).is_none() {
-                T::ReferralManager::create_referral_relationship(&source, &dest);
-            }
             <Self as fungible::Mutate<_>>::transfer(&source, &dest, value, Expendable)?;
             Ok(().into())
         }
@@ -848,6 +967,116 @@ pub mod pallet {
             });
             Ok(().into())
         }
+
+        /// Block all transfers to and from `who`.
+        ///
+        /// The dispatch origin for this call is `TransferListOrigin`.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn add_to_transfer_deny_list(
+            origin: OriginFor<T>,
+            who: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::TransferListOrigin::ensure_origin(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            TransferDenyList::<T, I>::insert(&who, ());
+            Self::deposit_event(Event::AddedToTransferDenyList { who });
+            Ok(())
+        }
+
+        /// Lift a transfer block placed by `add_to_transfer_deny_list`.
+        ///
+        /// The dispatch origin for this call is `TransferListOrigin`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn remove_from_transfer_deny_list(
+            origin: OriginFor<T>,
+            who: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::TransferListOrigin::ensure_origin(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            TransferDenyList::<T, I>::remove(&who);
+            Self::deposit_event(Event::RemovedFromTransferDenyList { who });
+            Ok(())
+        }
+
+        /// Exempt transfers touching `who` from `TransferFilter`.
+        ///
+        /// The dispatch origin for this call is `TransferListOrigin`.
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn add_to_transfer_allow_list(
+            origin: OriginFor<T>,
+            who: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::TransferListOrigin::ensure_origin(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            TransferAllowList::<T, I>::insert(&who, ());
+            Self::deposit_event(Event::AddedToTransferAllowList { who });
+            Ok(())
+        }
+
+        /// Remove an exemption granted by `add_to_transfer_allow_list`.
+        ///
+        /// The dispatch origin for this call is `TransferListOrigin`.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn remove_from_transfer_allow_list(
+            origin: OriginFor<T>,
+            who: AccountIdLookupOf<T>,
+        ) -> DispatchResult {
+            T::TransferListOrigin::ensure_origin(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            TransferAllowList::<T, I>::remove(&who);
+            Self::deposit_event(Event::RemovedFromTransferAllowList { who });
+            Ok(())
+        }
+
+        /// Limit how much may leave the caller's account within `window` blocks.
+        ///
+        /// A limit that is no looser than the current one, in both amount and window, applies
+        /// immediately and cancels any pending change. Anything else waits
+        /// `TransferLimitIncreaseDelay` blocks. Multi-signature accounts of d9-multi-sig may set
+        /// their own limit by executing this call.
+        ///
+        /// The dispatch origin for this call must be `Signed`.
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub fn set_transfer_limit(
+            origin: OriginFor<T>,
+            amount: T::Balance,
+            window: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!window.is_zero(), Error::<T, I>::InvalidTransferLimit);
+            let limit = TransferLimit { amount, window };
+            match Self::apply_due_transfer_limit(&who) {
+                Some(current) if !limit.is_stricter_than(&current) => {
+                    Self::schedule_transfer_limit(who, Some(limit));
+                }
+                _ => {
+                    PendingTransferLimits::<T, I>::remove(&who);
+                    TransferLimits::<T, I>::insert(&who, limit.clone());
+                    Self::deposit_event(Event::TransferLimitSet { who, limit: Some(limit) });
+                }
+            }
+            Ok(())
+        }
+
+        /// Schedule removal of the caller's transfer limit after `TransferLimitIncreaseDelay`.
+        ///
+        /// The dispatch origin for this call must be `Signed`.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn remove_transfer_limit(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::apply_due_transfer_limit(&who).is_some(),
+                Error::<T, I>::NoTransferLimit
+            );
+            Self::schedule_transfer_limit(who, None);
+            Ok(())
+        }
     }
 
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
@@ -996,8 +1225,174 @@ pub mod pallet {
         }
 
         fn ensure_unlocked(account_id: &T::AccountId) -> Result<(), DispatchError> {
-            let is_locked = Locks::<T, I>::contains_key(account_id);
-            ensure!(!is_locked, Error::<T, I>::LiquidityRestrictions);
+            ensure!(!Self::is_frozen_by_lock(account_id), Error::<T, I>::LiquidityRestrictions);
+            Ok(())
+        }
+
+        /// `true` if `who` holds a lock listed in `T::AccountFreezingLocks`.
+        pub(crate) fn is_frozen_by_lock(who: &T::AccountId) -> bool {
+            Locks::<T, I>::get(who)
+                .iter()
+                .any(|lock| T::AccountFreezingLocks::contains(&lock.id))
+        }
+
+        /// Weight of the checks and bookkeeping every transfer call does on top of the benchmarked
+        /// transfer: the lock check, the deny and allow lists, `T::TransferFilter`, the sender's
+        /// transfer limit and the recipient's referral.
+        pub fn transfer_checks_weight() -> Weight {
+            T::DbWeight::get()
+                .reads_writes(11, 5)
+                .saturating_add(T::TransferFilter::weight())
+        }
+
+        /// Check a transfer against the deny list and `T::TransferFilter`.
+        ///
+        /// A denied account on either side always blocks the transfer. The filter is skipped when
+        /// either side is on the allow list. Emits `TransferBlocked` before failing with the error
+        /// of the same name. The event is kept when the caller handles the error, as a hook or
+        /// another pallet may; a failing extrinsic rolls it back and reports the error instead.
+        pub(crate) fn ensure_transfer_allowed(
+            from: &T::AccountId,
+            to: &T::AccountId,
+            amount: T::Balance,
+        ) -> DispatchResult {
+            let denied = TransferDenyList::<T, I>::contains_key(from)
+                || TransferDenyList::<T, I>::contains_key(to);
+            let exempt = TransferAllowList::<T, I>::contains_key(from)
+                || TransferAllowList::<T, I>::contains_key(to);
+            if denied || (!exempt && !T::TransferFilter::allow_transfer(from, to, amount)) {
+                Self::deposit_event(Event::TransferBlocked {
+                    from: from.clone(),
+                    to: to.clone(),
+                    amount,
+                });
+                return Err(Error::<T, I>::TransferBlocked.into());
+            }
+            Ok(())
+        }
+
+        /// Give `dest` the referral parent chosen by `T::ReferralPolicy`, if it has none yet.
+        ///
+        /// Called once the funds have arrived, so a failed transfer records no parent.
+        pub(crate) fn note_referral(
+            source: Option<&T::AccountId>,
+            dest: &T::AccountId,
+            endowed: bool,
+        ) {
+            if T::ReferralManager::get_parent(dest).is_some() {
+                return;
+            }
+            if let Some(parent) = T::ReferralPolicy::referral_parent(source, dest, endowed) {
+                T::ReferralManager::create_referral_relationship(&parent, dest);
+            }
+        }
+
+        /// The transfer limit in effect for `who`, counting a pending change that is due.
+        pub fn effective_transfer_limit(
+            who: &T::AccountId,
+        ) -> Option<TransferLimit<T::Balance, T::BlockNumber>> {
+            match PendingTransferLimits::<T, I>::get(who) {
+                Some(pending)
+                    if pending.effective_at <= frame_system::Pallet::<T>::block_number() =>
+                {
+                    pending.limit
+                }
+                _ => TransferLimits::<T, I>::get(who),
+            }
+        }
+
+        /// Store a pending transfer limit change of `who` that is due and return the limit in
+        /// effect.
+        pub(crate) fn apply_due_transfer_limit(
+            who: &T::AccountId,
+        ) -> Option<TransferLimit<T::Balance, T::BlockNumber>> {
+            if let Some(pending) = PendingTransferLimits::<T, I>::get(who) {
+                if pending.effective_at <= frame_system::Pallet::<T>::block_number() {
+                    PendingTransferLimits::<T, I>::remove(who);
+                    match pending.limit.clone() {
+                        Some(limit) => TransferLimits::<T, I>::insert(who, limit),
+                        None => {
+                            TransferLimits::<T, I>::remove(who);
+                            TransferLimitUsage::<T, I>::remove(who);
+                        }
+                    }
+                    Self::deposit_event(Event::TransferLimitSet {
+                        who: who.clone(),
+                        limit: pending.limit,
+                    });
+                }
+            }
+            TransferLimits::<T, I>::get(who)
+        }
+
+        fn schedule_transfer_limit(
+            who: T::AccountId,
+            limit: Option<TransferLimit<T::Balance, T::BlockNumber>>,
+        ) {
+            let effective_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::TransferLimitIncreaseDelay::get());
+            PendingTransferLimits::<T, I>::insert(
+                &who,
+                PendingTransferLimit { limit: limit.clone(), effective_at },
+            );
+            Self::deposit_event(Event::TransferLimitChangeScheduled { who, limit, effective_at });
+        }
+
+        /// Count `amount` against the transfer limit of `from`, failing if it would be exceeded.
+        ///
+        /// The window rolls: a transfer counts against the limit until `window` blocks after the
+        /// bucket it was recorded in closed. Since a bucket spans `window / TransferLimitBuckets`
+        /// blocks, rounded up, a transfer is counted for at least `window` blocks and for no more
+        /// than one bucket longer, so no `window` blocks ever see more than the limit leave.
+        ///
+        /// With `Fortitude::Force` the amount is counted even if it exceeds the limit, for moves
+        /// the account holder must not be able to veto, such as repatriating a slashed reserve.
+        pub(crate) fn charge_transfer_limit(
+            from: &T::AccountId,
+            amount: T::Balance,
+            fortitude: Fortitude,
+        ) -> DispatchResult {
+            let limit = match Self::apply_due_transfer_limit(from) {
+                Some(limit) => limit,
+                None => return Ok(()),
+            };
+            let now = frame_system::Pallet::<T>::block_number();
+            let max_buckets = T::TransferLimitBuckets::get().max(1);
+            let bucket_length = limit
+                .window
+                .saturating_add(T::BlockNumber::from(max_buckets - 1))
+                / T::BlockNumber::from(max_buckets);
+            let mut buckets = TransferLimitUsage::<T, I>::get(from).into_inner();
+            buckets.retain(|bucket| {
+                bucket.start.saturating_add(bucket_length).saturating_add(limit.window) > now
+            });
+            let spent = buckets
+                .iter()
+                .fold(amount, |spent, bucket| spent.saturating_add(bucket.spent));
+            ensure!(
+                spent <= limit.amount || fortitude == Force,
+                Error::<T, I>::TransferLimitExceeded
+            );
+
+            match buckets.last_mut() {
+                Some(bucket) if now < bucket.start.saturating_add(bucket_length) => {
+                    bucket.spent = bucket.spent.saturating_add(amount);
+                }
+                _ => {
+                    let mut recorded = amount;
+                    if buckets.len() >= max_buckets as usize {
+                        // fold the oldest bucket into a newer one, which counts it for longer
+                        // rather than shorter
+                        let oldest = buckets.remove(0);
+                        match buckets.first_mut() {
+                            Some(next) => next.spent = next.spent.saturating_add(oldest.spent),
+                            None => recorded = recorded.saturating_add(oldest.spent),
+                        }
+                    }
+                    buckets.push(TransferBucket { start: now, spent: recorded });
+                }
+            }
+            TransferLimitUsage::<T, I>::insert(from, BoundedVec::truncate_from(buckets));
             Ok(())
         }
 
@@ -1082,9 +1477,10 @@ pub mod pallet {
                     *maybe_account = Some(account);
                     None
                 };
-                Ok((maybe_endowed, maybe_dust, result))
+                let reaped = !is_new && maybe_account.is_none();
+                Ok((maybe_endowed, maybe_dust, reaped, result))
             });
-            result.map(|(maybe_endowed, maybe_dust, result)| {
+            result.map(|(maybe_endowed, maybe_dust, reaped, result)| {
                 //todo change here
                 if let Some(endowed) = maybe_endowed {
                     Self::deposit_event(Event::Endowed {
@@ -1098,6 +1494,9 @@ pub mod pallet {
                         amount,
                     });
                 }
+                if reaped && !frame_system::Pallet::<T>::account_exists(who) {
+                    T::OnAccountReaped::on_killed_account(who);
+                }
                 (result, maybe_dust)
             })
         }
@@ -1246,6 +1645,7 @@ pub mod pallet {
                     Status::Reserved => Ok(actual),
                 };
             }
+            Self::ensure_transfer_allowed(slashed, beneficiary, actual)?;
 
             let ((_, maybe_dust_1), maybe_dust_2) = Self::try_mutate_account(
                 beneficiary,
@@ -1278,6 +1678,9 @@ pub mod pallet {
             if let Some(dust) = maybe_dust_2 {
                 <Self as fungible::Unbalanced<_>>::handle_raw_dust(dust);
             }
+            // reserves are repatriated by other pallets, often as a slash, so the limit counts
+            // the amount without being able to stop it
+            Self::charge_transfer_limit(slashed, actual, Force)?;
 
             Self::deposit_event(Event::ReserveRepatriated {
                 from: slashed.clone(),
@@ -1294,4 +1697,75 @@ pub mod pallet {
 
         fn create_referral_relationship(parent: &T::AccountId, child: &T::AccountId) -> ();
     }
+
+    /// Picks the referral parent of `dest` when it receives funds and has no parent yet.
+    ///
+    /// `source` is `None` when the funds do not come from another account, as with
+    /// `deposit_creating`. `endowed` is `true` when `dest` did not exist before.
+    pub trait ReferralPolicy<AccountId> {
+        fn referral_parent(
+            source: Option<&AccountId>,
+            dest: &AccountId,
+            endowed: bool,
+        ) -> Option<AccountId>;
+    }
+
+    /// Never create referral relationships.
+    impl<AccountId> ReferralPolicy<AccountId> for () {
+        fn referral_parent(_: Option<&AccountId>, _: &AccountId, _: bool) -> Option<AccountId> {
+            None
+        }
+    }
+
+    /// The first account to send funds to `dest` becomes its parent.
+    pub struct ParentIsSender;
+    impl<AccountId: Clone> ReferralPolicy<AccountId> for ParentIsSender {
+        fn referral_parent(source: Option<&AccountId>, _: &AccountId, _: bool) -> Option<AccountId> {
+            source.cloned()
+        }
+    }
+
+    /// The account whose transfer creates `dest` becomes its parent.
+    pub struct ParentIsEndowingSender;
+    impl<AccountId: Clone> ReferralPolicy<AccountId> for ParentIsEndowingSender {
+        fn referral_parent(
+            source: Option<&AccountId>,
+            _: &AccountId,
+            endowed: bool,
+        ) -> Option<AccountId> {
+            source.filter(|_| endowed).cloned()
+        }
+    }
+
+    /// Use `P`, falling back to `Default` for accounts endowed without a parent.
+    pub struct OrDefaultParent<P, Default>(PhantomData<(P, Default)>);
+    impl<AccountId, P, Default> ReferralPolicy<AccountId> for OrDefaultParent<P, Default>
+    where
+        P: ReferralPolicy<AccountId>,
+        Default: Get<AccountId>,
+    {
+        fn referral_parent(
+            source: Option<&AccountId>,
+            dest: &AccountId,
+            endowed: bool,
+        ) -> Option<AccountId> {
+            P::referral_parent(source, dest, endowed).or_else(|| endowed.then(Default::get))
+        }
+    }
+
+    /// Decides whether a transfer of `amount` from `from` to `to` may go ahead.
+    pub trait TransferFilter<AccountId, Balance> {
+        fn allow_transfer(from: &AccountId, to: &AccountId, amount: Balance) -> bool;
+
+        /// Weight of `allow_transfer`, charged on every transfer call.
+        fn weight() -> Weight {
+            Weight::zero()
+        }
+    }
+
+    impl<AccountId, Balance> TransferFilter<AccountId, Balance> for () {
+        fn allow_transfer(_: &AccountId, _: &AccountId, _: Balance) -> bool {
+            true
+        }
+    }
 }
@@ -0,0 +1,196 @@
+use crate as pallet_d9_balances;
+use crate::*;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU32, ConstU64, Contains, LockIdentifier, OnKilledAccount},
+    weights::Weight,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_d9_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+        MultiSig: pallet_d9_multi_sig::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const FreezingLockId: LockIdentifier = *b"freezing";
+    /// transfers to these accounts are rejected by `TestTransferFilter`
+    pub static FilteredAccounts: Vec<AccountId> = vec![];
+    /// accounts reported to `RecordReaped`
+    pub static ReapedAccounts: Vec<AccountId> = vec![];
+    /// `(child, parent)` pairs recorded by `TestReferrals`
+    pub static ReferralParents: Vec<(AccountId, AccountId)> = vec![];
+    /// policy followed by `TestReferralPolicy`
+    pub static ActiveReferralPolicy: TestPolicy = TestPolicy::NoReferrals;
+    pub const DefaultReferralParent: AccountId = 99;
+}
+
+pub struct TestReferrals;
+impl ReferralManager<Test, ()> for TestReferrals {
+    fn get_parent(child: &AccountId) -> Option<AccountId> {
+        ReferralParents::get()
+            .iter()
+            .find(|(recorded, _)| recorded == child)
+            .map(|(_, parent)| *parent)
+    }
+
+    fn create_referral_relationship(parent: &AccountId, child: &AccountId) {
+        ReferralParents::mutate(|parents| parents.push((*child, *parent)));
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum TestPolicy {
+    NoReferrals,
+    Sender,
+    EndowingSender,
+    EndowingSenderOrDefault,
+}
+
+/// follows the policy chosen through `ActiveReferralPolicy`
+pub struct TestReferralPolicy;
+impl ReferralPolicy<AccountId> for TestReferralPolicy {
+    fn referral_parent(
+        source: Option<&AccountId>,
+        dest: &AccountId,
+        endowed: bool,
+    ) -> Option<AccountId> {
+        match ActiveReferralPolicy::get() {
+            TestPolicy::NoReferrals => {
+                <() as ReferralPolicy<_>>::referral_parent(source, dest, endowed)
+            }
+            TestPolicy::Sender => ParentIsSender::referral_parent(source, dest, endowed),
+            TestPolicy::EndowingSender => {
+                ParentIsEndowingSender::referral_parent(source, dest, endowed)
+            }
+            TestPolicy::EndowingSenderOrDefault => OrDefaultParent::<
+                ParentIsEndowingSender,
+                DefaultReferralParent,
+            >::referral_parent(source, dest, endowed),
+        }
+    }
+}
+
+/// only `FreezingLockId` freezes a whole account
+pub struct FreezingLocks;
+impl Contains<LockIdentifier> for FreezingLocks {
+    fn contains(id: &LockIdentifier) -> bool {
+        *id == FreezingLockId::get()
+    }
+}
+
+pub struct RecordReaped;
+impl OnKilledAccount<AccountId> for RecordReaped {
+    fn on_killed_account(who: &AccountId) {
+        ReapedAccounts::mutate(|reaped| reaped.push(*who));
+    }
+}
+
+pub struct TestTransferFilter;
+impl TransferFilter<AccountId, u64> for TestTransferFilter {
+    fn allow_transfer(_: &AccountId, to: &AccountId, _: u64) -> bool {
+        !FilteredAccounts::get().contains(to)
+    }
+
+    fn weight() -> Weight {
+        Weight::from_parts(1_000, 0)
+    }
+}
+
+impl pallet_d9_balances::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Balance = u64;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<10>;
+    type AccountStore = System;
+    type ReserveIdentifier = [u8; 8];
+    type HoldIdentifier = ();
+    type FreezeIdentifier = ();
+    type MaxLocks = ConstU32<10>;
+    type MaxReserves = ConstU32<2>;
+    type MaxHolds = ConstU32<2>;
+    type MaxFreezes = ConstU32<2>;
+    type ReferralManager = TestReferrals;
+    type ReferralPolicy = TestReferralPolicy;
+    type OnAccountReaped = RecordReaped;
+    type AccountFreezingLocks = FreezingLocks;
+    type TransferFilter = TestTransferFilter;
+    type TransferListOrigin = EnsureRoot<AccountId>;
+    type TransferLimitIncreaseDelay = ConstU64<10>;
+    type TransferLimitBuckets = ConstU32<5>;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_d9_multi_sig::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxSignatories = ConstU32<5>;
+    type MaxPendingCalls = ConstU32<10>;
+    type MaxMultiSigsPerAccountId = ConstU32<3>;
+    type RuntimeCall = RuntimeCall;
+    type MaxCallSize = ConstU32<100>;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_d9_balances::GenesisConfig::<Test> {
+        balances: (1..=5).map(|account| (account, 1_000)).collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
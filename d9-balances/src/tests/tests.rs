@@ -0,0 +1,476 @@
+use super::mock::*;
+use crate::*;
+use frame_support::{
+    assert_err, assert_noop, assert_ok,
+    dispatch::GetDispatchInfo,
+    traits::{
+        fungible::{self, MutateHold},
+        tokens::{Fortitude, Precision, Preservation, Restriction},
+        BalanceStatus, Currency, ExistenceRequirement, LockableCurrency, ReservableCurrency,
+        WithdrawReasons,
+    },
+    weights::Weight,
+};
+use sp_runtime::{DispatchError, TokenError};
+
+fn transfer_blocked(from: AccountId, to: AccountId, amount: u64) -> RuntimeEvent {
+    RuntimeEvent::Balances(Event::TransferBlocked { from, to, amount })
+}
+
+#[test]
+fn deny_list_blocks_transfers_both_ways() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Balances::add_to_transfer_deny_list(RuntimeOrigin::signed(1), 2),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Balances::add_to_transfer_deny_list(RuntimeOrigin::root(), 2));
+        assert_err!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 100),
+            Error::<Test>::TransferBlocked
+        );
+        System::assert_last_event(transfer_blocked(1, 2, 100));
+        assert_err!(
+            Balances::transfer_keep_alive(RuntimeOrigin::signed(2), 1, 100),
+            Error::<Test>::TransferBlocked
+        );
+        System::assert_last_event(transfer_blocked(2, 1, 100));
+        assert_eq!(Balances::free_balance(1), 1_000);
+        assert_eq!(Balances::free_balance(2), 1_000);
+
+        assert_ok!(Balances::remove_from_transfer_deny_list(RuntimeOrigin::root(), 2));
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 100));
+        assert_eq!(Balances::free_balance(2), 1_100);
+    });
+}
+
+#[test]
+fn allow_list_skips_the_filter_but_not_the_deny_list() {
+    new_test_ext().execute_with(|| {
+        FilteredAccounts::set(vec![3]);
+        assert_err!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 3, 100),
+            Error::<Test>::TransferBlocked
+        );
+        System::assert_last_event(transfer_blocked(1, 3, 100));
+
+        assert_ok!(Balances::add_to_transfer_allow_list(RuntimeOrigin::root(), 1));
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 3, 100));
+        assert_eq!(Balances::free_balance(3), 1_100);
+
+        assert_ok!(Balances::add_to_transfer_deny_list(RuntimeOrigin::root(), 3));
+        assert_err!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 3, 100),
+            Error::<Test>::TransferBlocked
+        );
+        assert_eq!(Balances::free_balance(3), 1_100);
+        FilteredAccounts::set(vec![]);
+    });
+}
+
+#[test]
+fn transfer_calls_charge_the_transfer_checks() {
+    new_test_ext().execute_with(|| {
+        let checks = Balances::transfer_checks_weight();
+        assert!(checks.all_gte(Weight::from_parts(1_000, 0)));
+        let calls: Vec<(RuntimeCall, Weight)> = vec![
+            (
+                RuntimeCall::Balances(Call::transfer_allow_death { dest: 2, value: 100 }),
+                <() as WeightInfo>::transfer_allow_death(),
+            ),
+            (
+                RuntimeCall::Balances(Call::force_transfer { source: 1, dest: 2, value: 100 }),
+                <() as WeightInfo>::force_transfer(),
+            ),
+            (
+                RuntimeCall::Balances(Call::transfer_keep_alive { dest: 2, value: 100 }),
+                <() as WeightInfo>::transfer_keep_alive(),
+            ),
+            (
+                RuntimeCall::Balances(Call::transfer_all { dest: 2, keep_alive: true }),
+                <() as WeightInfo>::transfer_all(),
+            ),
+            (
+                RuntimeCall::Balances(Call::transfer { dest: 2, value: 100 }),
+                <() as WeightInfo>::transfer_allow_death(),
+            ),
+        ];
+        for (call, benchmarked) in calls {
+            assert_eq!(call.get_dispatch_info().weight, benchmarked.saturating_add(checks));
+        }
+    });
+}
+
+#[test]
+fn reserved_and_held_funds_cannot_reach_denied_accounts() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 100));
+        assert_ok!(<Balances as MutateHold<_>>::hold(&(), &1, 100));
+        assert_ok!(Balances::add_to_transfer_deny_list(RuntimeOrigin::root(), 2));
+
+        assert_err!(
+            <Balances as ReservableCurrency<_>>::repatriate_reserved(
+                &1,
+                &2,
+                50,
+                BalanceStatus::Free
+            ),
+            Error::<Test>::TransferBlocked
+        );
+        System::assert_last_event(transfer_blocked(1, 2, 50));
+        assert_err!(
+            <Balances as MutateHold<_>>::transfer_on_hold(
+                &(),
+                &1,
+                &2,
+                50,
+                Precision::Exact,
+                Restriction::Free,
+                Fortitude::Polite
+            ),
+            Error::<Test>::TransferBlocked
+        );
+        assert_eq!(Balances::reserved_balance(1), 200);
+        assert_eq!(Balances::free_balance(2), 1_000);
+
+        assert_ok!(Balances::remove_from_transfer_deny_list(RuntimeOrigin::root(), 2));
+        assert_ok!(<Balances as MutateHold<_>>::transfer_on_hold(
+            &(),
+            &1,
+            &2,
+            50,
+            Precision::Exact,
+            Restriction::Free,
+            Fortitude::Polite
+        ));
+        assert_eq!(Balances::free_balance(2), 1_050);
+    });
+}
+
+#[test]
+fn transfer_limit_window_rolls_instead_of_resetting() {
+    new_test_ext().execute_with(|| {
+        // 100 per 10 blocks, tracked in 5 buckets of 2 blocks
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(1), 100, 10));
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 60));
+        System::set_block_number(9);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 40));
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 1),
+            Error::<Test>::TransferLimitExceeded
+        );
+
+        // a fixed window opened at block 1 would have reset here
+        System::set_block_number(11);
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 50),
+            Error::<Test>::TransferLimitExceeded
+        );
+
+        // the 60 sent in the bucket opened at block 1 no longer counts, the 40 from block 9 does
+        System::set_block_number(13);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 60));
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 1),
+            Error::<Test>::TransferLimitExceeded
+        );
+        assert_eq!(Balances::free_balance(2), 1_160);
+    });
+}
+
+#[test]
+fn failed_transfers_do_not_count_against_the_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(1), 2_000, 10));
+        let transfer = |amount| {
+            <Balances as fungible::Mutate<_>>::transfer(&1, &2, amount, Preservation::Expendable)
+        };
+        assert_noop!(transfer(1_500), TokenError::FundsUnavailable);
+        assert!(TransferLimitUsage::<Test>::get(1).is_empty());
+
+        assert_ok!(transfer(900));
+        assert_eq!(TransferLimitUsage::<Test>::get(1).into_inner()[0].spent, 900);
+    });
+}
+
+#[test]
+fn reading_the_transfer_limit_does_not_apply_pending_changes() {
+    new_test_ext().execute_with(|| {
+        let limit = |amount| TransferLimit { amount, window: 10 };
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(1), 100, 10));
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(1), 200, 10));
+        assert_eq!(Balances::effective_transfer_limit(&1), Some(limit(100)));
+
+        System::set_block_number(11);
+        assert_eq!(Balances::effective_transfer_limit(&1), Some(limit(200)));
+        assert_eq!(Balances::transfer_limits(1), Some(limit(100)));
+        assert!(Balances::pending_transfer_limits(1).is_some());
+
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 150));
+        assert_eq!(Balances::transfer_limits(1), Some(limit(200)));
+        assert_eq!(Balances::pending_transfer_limits(1), None);
+    });
+}
+
+#[test]
+fn reserved_and_held_transfers_count_against_the_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(1), 100, 10));
+        assert_ok!(<Balances as ReservableCurrency<_>>::reserve(&1, 200));
+        // a repatriated reserve may be a slash, so it is counted but never refused
+        assert_ok!(<Balances as ReservableCurrency<_>>::repatriate_reserved(
+            &1,
+            &2,
+            150,
+            BalanceStatus::Free
+        ));
+        assert_eq!(Balances::free_balance(2), 1_150);
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 1),
+            Error::<Test>::TransferLimitExceeded
+        );
+
+        assert_ok!(Balances::set_transfer_limit(RuntimeOrigin::signed(3), 100, 10));
+        assert_ok!(<Balances as MutateHold<_>>::hold(&(), &3, 200));
+        let transfer_on_hold = |amount, force| {
+            <Balances as MutateHold<_>>::transfer_on_hold(
+                &(),
+                &3,
+                &2,
+                amount,
+                Precision::Exact,
+                Restriction::Free,
+                force,
+            )
+        };
+        assert_noop!(
+            transfer_on_hold(150, Fortitude::Polite),
+            Error::<Test>::TransferLimitExceeded
+        );
+        assert_ok!(transfer_on_hold(80, Fortitude::Polite));
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(3), 2, 30),
+            Error::<Test>::TransferLimitExceeded
+        );
+        assert_ok!(transfer_on_hold(50, Fortitude::Force));
+        assert_eq!(Balances::free_balance(2), 1_280);
+    });
+}
+
+#[test]
+fn multisig_accounts_set_and_obey_their_own_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(MultiSig::create_multi_sig_account(
+            RuntimeOrigin::signed(1),
+            vec![1, 2],
+            None,
+            2
+        ));
+        let (msa, _) = pallet_d9_multi_sig::MultiSignatureAccounts::<Test>::iter()
+            .next()
+            .unwrap();
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), msa, 500));
+
+        let author = |call: Call<Test>| {
+            assert_ok!(MultiSig::author_a_call(
+                RuntimeOrigin::signed(1),
+                msa,
+                Box::new(RuntimeCall::Balances(call))
+            ));
+            pallet_d9_multi_sig::MultiSignatureAccounts::<Test>::get(msa)
+                .unwrap()
+                .pending_calls[0]
+                .id
+        };
+        let call_id = author(Call::set_transfer_limit {
+            amount: 100,
+            window: 10,
+        });
+        assert_ok!(MultiSig::add_call_approval(RuntimeOrigin::signed(2), msa, call_id));
+        assert_eq!(
+            Balances::transfer_limits(msa),
+            Some(TransferLimit { amount: 100, window: 10 })
+        );
+
+        let call_id = author(Call::transfer_allow_death { dest: 3, value: 60 });
+        assert_ok!(MultiSig::add_call_approval(RuntimeOrigin::signed(2), msa, call_id));
+        let call_id = author(Call::transfer_allow_death { dest: 3, value: 50 });
+        assert_noop!(
+            MultiSig::add_call_approval(RuntimeOrigin::signed(2), msa, call_id),
+            Error::<Test>::TransferLimitExceeded
+        );
+        assert_eq!(Balances::free_balance(msa), 440);
+        assert_eq!(Balances::free_balance(3), 1_060);
+    });
+}
+
+#[test]
+fn reaping_an_account_notifies_the_handler() {
+    new_test_ext().execute_with(|| {
+        ReapedAccounts::set(vec![]);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(5), 1, 1_000));
+        assert!(!System::account_exists(&5));
+        assert_eq!(ReapedAccounts::get(), vec![5]);
+
+        // dust below the existential deposit reaps the account as well
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(4), 1, 995));
+        assert!(!System::account_exists(&4));
+        assert_eq!(ReapedAccounts::get(), vec![5, 4]);
+    });
+}
+
+#[test]
+fn accounts_kept_alive_by_sufficients_are_not_reported() {
+    new_test_ext().execute_with(|| {
+        ReapedAccounts::set(vec![]);
+        System::inc_sufficients(&5);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(5), 1, 1_000));
+        assert_eq!(Balances::free_balance(5), 0);
+        assert!(System::account_exists(&5));
+        assert!(ReapedAccounts::get().is_empty());
+
+        // nor is one kept alive by another provider
+        System::inc_providers(&6);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 6, 100));
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(6), 1, 100));
+        assert!(System::account_exists(&6));
+        assert!(ReapedAccounts::get().is_empty());
+    });
+}
+
+#[test]
+fn only_freezing_locks_block_the_whole_balance() {
+    new_test_ext().execute_with(|| {
+        Balances::set_lock(*b"partlock", &1, 100, WithdrawReasons::all());
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 800));
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 150),
+            TokenError::Frozen
+        );
+
+        Balances::set_lock(FreezingLockId::get(), &1, 1, WithdrawReasons::all());
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 10),
+            Error::<Test>::LiquidityRestrictions
+        );
+        assert_noop!(
+            <Balances as Currency<_>>::withdraw(
+                &1,
+                10,
+                WithdrawReasons::TRANSFER,
+                ExistenceRequirement::AllowDeath
+            ),
+            Error::<Test>::LiquidityRestrictions
+        );
+
+        Balances::remove_lock(FreezingLockId::get(), &1);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 10));
+        assert_eq!(Balances::free_balance(2), 1_810);
+    });
+}
+
+fn referral_parent(child: AccountId) -> Option<AccountId> {
+    TestReferrals::get_parent(&child)
+}
+
+#[test]
+fn sender_becomes_the_parent_on_every_entry_point() {
+    new_test_ext().execute_with(|| {
+        ActiveReferralPolicy::set(TestPolicy::Sender);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 10, 100));
+        assert_eq!(referral_parent(10), Some(1));
+        // an existing account without a parent gets one as well
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(2), 3, true));
+        assert_eq!(referral_parent(3), Some(2));
+        assert_ok!(<Balances as Currency<_>>::transfer(
+            &4,
+            &11,
+            100,
+            ExistenceRequirement::AllowDeath
+        ));
+        assert_eq!(referral_parent(11), Some(4));
+
+        // nor is an existing parent overwritten
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(5), 10, false));
+        assert_ok!(<Balances as Currency<_>>::transfer(
+            &4,
+            &3,
+            100,
+            ExistenceRequirement::KeepAlive
+        ));
+        assert_eq!(referral_parent(10), Some(1));
+        assert_eq!(referral_parent(3), Some(2));
+
+        // funds that come from no account name no parent
+        drop(<Balances as Currency<_>>::deposit_creating(&12, 100));
+        assert_eq!(referral_parent(12), None);
+        assert_eq!(ReferralParents::get().len(), 3);
+    });
+}
+
+#[test]
+fn only_the_endowing_sender_becomes_the_parent() {
+    new_test_ext().execute_with(|| {
+        ActiveReferralPolicy::set(TestPolicy::EndowingSender);
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 100));
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(3), 4, true));
+        assert_eq!(referral_parent(2), None);
+        assert_eq!(referral_parent(4), None);
+
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(5), 10, true));
+        assert_eq!(referral_parent(10), Some(5));
+        assert_ok!(<Balances as Currency<_>>::transfer(
+            &1,
+            &11,
+            100,
+            ExistenceRequirement::KeepAlive
+        ));
+        assert_eq!(referral_parent(11), Some(1));
+        drop(<Balances as Currency<_>>::deposit_creating(&12, 100));
+        assert_eq!(referral_parent(12), None);
+    });
+}
+
+#[test]
+fn accounts_endowed_without_a_sender_get_the_default_parent() {
+    new_test_ext().execute_with(|| {
+        ActiveReferralPolicy::set(TestPolicy::EndowingSenderOrDefault);
+        drop(<Balances as Currency<_>>::deposit_creating(&10, 100));
+        assert_eq!(referral_parent(10), Some(DefaultReferralParent::get()));
+        drop(<Balances as Currency<_>>::deposit_creating(&1, 100));
+        assert_eq!(referral_parent(1), None);
+
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), 11, 100));
+        assert_eq!(referral_parent(11), Some(2));
+        // the default never replaces a parent
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(3), 10, false));
+        assert_eq!(referral_parent(10), Some(DefaultReferralParent::get()));
+    });
+}
+
+#[test]
+fn failed_transfers_record_no_parent() {
+    new_test_ext().execute_with(|| {
+        ActiveReferralPolicy::set(TestPolicy::Sender);
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(1), 10, 5),
+            TokenError::BelowMinimum
+        );
+        assert_noop!(
+            <Balances as Currency<_>>::transfer(
+                &1,
+                &10,
+                2_000,
+                ExistenceRequirement::AllowDeath
+            ),
+            TokenError::FundsUnavailable
+        );
+        assert_ok!(Balances::add_to_transfer_deny_list(RuntimeOrigin::root(), 10));
+        assert_err!(
+            Balances::transfer_all(RuntimeOrigin::signed(1), 10, false),
+            Error::<Test>::TransferBlocked
+        );
+        assert_eq!(referral_parent(10), None);
+        assert!(ReferralParents::get().is_empty());
+    });
+}
@@ -143,6 +143,43 @@ impl<Balance: Saturating + Copy + Ord> AccountData<Balance> {
     }
 }
 
+/// An outbound transfer limit chosen by the account holder.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct TransferLimit<Balance, BlockNumber> {
+    /// The most that may leave the account within a single window.
+    pub amount: Balance,
+    /// The length of a window in blocks.
+    pub window: BlockNumber,
+}
+
+impl<Balance: PartialOrd, BlockNumber: PartialOrd> TransferLimit<Balance, BlockNumber> {
+    /// `true` if `self` allows no more than `other` does, so it may apply without delay.
+    pub fn is_stricter_than(&self, other: &Self) -> bool {
+        self.amount <= other.amount && self.window >= other.window
+    }
+}
+
+/// A loosening of a `TransferLimit` waiting out `Config::TransferLimitIncreaseDelay`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct PendingTransferLimit<Balance, BlockNumber> {
+    /// The limit to apply, `None` removes the limit.
+    pub limit: Option<TransferLimit<Balance, BlockNumber>>,
+    /// The block from which `limit` applies.
+    pub effective_at: BlockNumber,
+}
+
+/// Outbound volume of an account over a sub-period of its rolling limit window.
+///
+/// A bucket opens with the first outbound transfer after the previous bucket has closed and
+/// stays open for `window / Config::TransferLimitBuckets` blocks, rounded up.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct TransferBucket<Balance, BlockNumber> {
+    /// The block at which the bucket opened.
+    pub start: BlockNumber,
+    /// The amount sent while the bucket was open.
+    pub spent: Balance,
+}
+
 pub struct DustCleaner<T: Config<I>, I: 'static = ()>(
     pub(crate) Option<(T::AccountId, CreditOf<T, I>)>,
 );
@@ -1,9 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use pallet_timestamp::{self as timestamp};
 use sp_staking::SessionIndex;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 mod types;
-// mod mock;
-// mod tests;
 use frame_support::{
     traits::{Currency, LockableCurrency, WithdrawReasons},
     PalletId,
@@ -12,12 +14,22 @@ pub use pallet::*;
 pub use types::*;
 pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type PalletAdminHandover<T> = d9_admin::AdminHandover<
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::BlockNumber,
+    PalletAdmin<T>,
+    PendingPalletAdmin<T>,
+>;
+/// root or the pallet admin
+pub type EnsurePalletAdmin<T> =
+    d9_admin::EnsureRootOrAdmin<<T as frame_system::Config>::AccountId, PalletAdmin<T>>;
 #[frame_support::pallet]
 pub mod pallet {
 
     use core::u32;
 
     use super::*;
+    use d9_admin::{AdminChange, AdminError, AdminErrors, PendingAdmin};
     use frame_support::{
         inherent::Vec,
         pallet_prelude::{DispatchResult, OptionQuery, StorageMap, ValueQuery, *},
@@ -56,6 +68,8 @@ pub mod pallet {
         type ProposalFee: Get<BalanceOf<Self>>;
         ///get an estimate of a session's duration
         type SessionTimeEstimator: SessionTimeEstimator<Self>;
+        /// timelock on handing over the pallet admin role, see `d9_admin`
+        type AdminTransferDelay: Get<Self::BlockNumber>;
     }
 
     type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
@@ -63,6 +77,12 @@ pub mod pallet {
     #[pallet::getter(fn pallet_admin)]
     pub type PalletAdmin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    /// successor proposed by the pallet admin, waiting to accept
+    #[pallet::storage]
+    #[pallet::getter(fn pending_pallet_admin)]
+    pub type PendingPalletAdmin<T: Config> =
+        StorageValue<_, PendingAdmin<T::AccountId, T::BlockNumber>, OptionQuery>;
+
     /// fee to proposal a lock on an account. paid by the proposer
     #[pallet::storage]
     #[pallet::getter(fn proposal_fee)]
@@ -110,6 +130,12 @@ pub mod pallet {
         AccountNominatedForUnlock(T::AccountId),
         VoteStarted,
         VoteEnded(T::AccountId, VoteResult),
+        /// (proposed admin, first block it may accept)
+        AdminProposed(T::AccountId, T::BlockNumber),
+        /// (old admin, new admin)
+        AdminChanged(Option<T::AccountId>, T::AccountId),
+        /// (proposed admin) whose proposal was withdrawn
+        AdminProposalCancelled(T::AccountId),
     }
 
     #[pallet::error]
@@ -130,15 +156,42 @@ pub mod pallet {
         NotValidCouncilMember,
         ErrorCalculatingVotes,
         VoterAlreadyVoted,
+        NoPendingAdmin,
+        NotPendingAdmin,
+        AdminTimelockActive,
+    }
+
+    impl<T: Config> AdminErrors for Error<T> {
+        const NO_PENDING_ADMIN: Self = Self::NoPendingAdmin;
+        const NOT_PENDING_ADMIN: Self = Self::NotPendingAdmin;
+        const TIMELOCK_ACTIVE: Self = Self::AdminTimelockActive;
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// hand the pallet admin role to `new_admin`
+        ///
+        /// root sets the admin at once. the admin only proposes `new_admin`, who takes over with
+        /// `accept_pallet_admin` once `AdminTransferDelay` blocks have passed.
         #[pallet::call_index(0)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
         pub fn set_pallet_admin(origin: OriginFor<T>, new_admin: T::AccountId) -> DispatchResult {
-            Self::root_or_admin(origin)?;
-            PalletAdmin::<T>::put(new_admin);
+            let caller = ensure_signed_or_root(origin)?;
+            let change = PalletAdminHandover::<T>::propose(
+                caller,
+                new_admin,
+                frame_system::Pallet::<T>::block_number(),
+                T::AdminTransferDelay::get(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            match change {
+                AdminChange::Proposed(proposed, acceptable_at) => {
+                    Self::deposit_event(Event::AdminProposed(proposed, acceptable_at))
+                }
+                AdminChange::Changed(old_admin, new_admin) => {
+                    Self::deposit_event(Event::AdminChanged(old_admin, new_admin))
+                }
+            }
             Ok(())
         }
 
@@ -327,24 +380,36 @@ pub mod pallet {
             
             Ok(())
         }
+
+        /// take over the pallet admin role proposed to the caller
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn accept_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let new_admin = ensure_signed(origin)?;
+            let old_admin = PalletAdminHandover::<T>::accept(
+                new_admin.clone(),
+                frame_system::Pallet::<T>::block_number(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminChanged(old_admin, new_admin));
+            Ok(())
+        }
+
+        /// withdraw the pending pallet admin proposal, root or the admin
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn cancel_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let caller = ensure_signed_or_root(origin)?;
+            let proposed = PalletAdminHandover::<T>::cancel(caller)
+                .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminProposalCancelled(proposed));
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
         fn root_or_admin(origin: OriginFor<T>) -> Result<(), BadOrigin> {
-            let origin = ensure_signed_or_root(origin)?;
-            match origin {
-                Some(caller) => {
-                    let admin = PalletAdmin::<T>::get();
-                    if admin.is_some() && admin.unwrap() == caller {
-                        return Ok(());
-                    } else {
-                        return Err(BadOrigin);
-                    }
-                }
-                None => {
-                    return Ok(());
-                }
-            }
+            EnsurePalletAdmin::<T>::ensure_origin(origin).map(|_| ())
         }
 
         fn estimate_time_to_referendum() -> Option<MomentOf<T>> {
@@ -1,2 +1,235 @@
-#![cfg(test)]
 use crate::mock::*;
+use crate::*;
+use frame_support::{assert_noop, assert_ok};
+use frame_system::RawOrigin;
+use sp_runtime::DispatchError;
+
+const NOMINATOR: AccountId = 1;
+const COUNCIL: [AccountId; 3] = [1, 2, 3];
+const OUTSIDER: AccountId = 4;
+const TARGET: AccountId = 7;
+const MINING_POOL: AccountId = 99;
+
+/// ranks 1 to 4, points the fees at the mining pool and charges 10 per proposal
+fn setup() {
+    RankedNodes::set(vec![1, 2, 3, OUTSIDER]);
+    assert_ok!(CouncilLock::set_mining_pool_contract(
+        RawOrigin::Root.into(),
+        MINING_POOL
+    ));
+    assert_ok!(CouncilLock::set_proposal_fee(RawOrigin::Root.into(), 10));
+}
+
+/// proposes to lock TARGET and opens the referendum once the waiting sessions have passed
+fn open_lock_referendum() {
+    assert_ok!(CouncilLock::propose_lock(
+        RawOrigin::Signed(NOMINATOR).into(),
+        TARGET,
+        10
+    ));
+    CurrentSession::set(2);
+    CouncilLock::start_pending_votes(2);
+    assert!(CouncilLock::proposals(TARGET).is_none());
+    assert!(CouncilLock::referendums(TARGET).is_some());
+}
+
+fn can_transfer(who: AccountId) -> bool {
+    Balances::transfer_allow_death(RuntimeOrigin::signed(who), 2, 10).is_ok()
+}
+
+#[test]
+fn proposing_a_lock_takes_the_fee_and_locks_the_account() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_ok!(CouncilLock::propose_lock(
+            RawOrigin::Signed(NOMINATOR).into(),
+            TARGET,
+            10
+        ));
+        System::assert_last_event(Event::AccountNominatedForLock(TARGET).into());
+        assert_eq!(Balances::free_balance(MINING_POOL), 10);
+        assert_eq!(Balances::free_balance(NOMINATOR), 9_990);
+        let proposal = CouncilLock::proposals(TARGET).unwrap();
+        assert_eq!(
+            (proposal.nominator, proposal.change_to),
+            (NOMINATOR, AccountLockState::Locked)
+        );
+        assert!(!can_transfer(TARGET));
+        assert_noop!(
+            CouncilLock::propose_lock(RawOrigin::Signed(2).into(), TARGET, 10),
+            Error::<Test>::ProposalAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn proposals_need_a_ranked_nominator_and_the_full_fee() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_noop!(
+            CouncilLock::propose_lock(RawOrigin::Signed(OUTSIDER).into(), TARGET, 10),
+            Error::<Test>::NotValidNominator
+        );
+        assert_noop!(
+            CouncilLock::propose_lock(RawOrigin::Signed(NOMINATOR).into(), TARGET, 9),
+            Error::<Test>::ProposalFeeInsufficient
+        );
+        assert_noop!(
+            CouncilLock::propose_unlock(RawOrigin::Signed(NOMINATOR).into(), TARGET, 10),
+            Error::<Test>::AccountNotLocked
+        );
+    });
+}
+
+#[test]
+fn an_accepted_lock_referendum_locks_the_account() {
+    new_test_ext().execute_with(|| {
+        setup();
+        open_lock_referendum();
+        assert_noop!(
+            CouncilLock::vote_in_referendum(RawOrigin::Signed(OUTSIDER).into(), TARGET, true),
+            Error::<Test>::NotValidCouncilMember
+        );
+        assert_ok!(CouncilLock::vote_in_referendum(
+            RawOrigin::Signed(COUNCIL[0]).into(),
+            TARGET,
+            true
+        ));
+        assert_noop!(
+            CouncilLock::vote_in_referendum(RawOrigin::Signed(COUNCIL[0]).into(), TARGET, true),
+            Error::<Test>::VoterAlreadyVoted
+        );
+        assert_ok!(CouncilLock::vote_in_referendum(
+            RawOrigin::Signed(COUNCIL[1]).into(),
+            TARGET,
+            true
+        ));
+        System::assert_has_event(Event::VoteEnded(TARGET, VoteResult::Passed).into());
+        System::assert_last_event(Event::AccountLocked(TARGET).into());
+        assert_eq!(
+            CouncilLock::locked_accounts(TARGET).unwrap().nominator,
+            NOMINATOR
+        );
+        assert!(CouncilLock::referendums(TARGET).is_none());
+        assert_eq!(
+            CouncilLock::resolutions((2, TARGET)).unwrap().result,
+            VoteResult::Passed
+        );
+        assert!(!can_transfer(TARGET));
+        assert_noop!(
+            CouncilLock::propose_lock(RawOrigin::Signed(TARGET).into(), 2, 10),
+            Error::<Test>::LockedAccountsNotPermittedToInteract
+        );
+    });
+}
+
+#[test]
+fn a_rejected_lock_referendum_unlocks_the_account() {
+    new_test_ext().execute_with(|| {
+        setup();
+        open_lock_referendum();
+        for voter in [COUNCIL[1], COUNCIL[2]] {
+            assert_ok!(CouncilLock::vote_in_referendum(
+                RawOrigin::Signed(voter).into(),
+                TARGET,
+                false
+            ));
+        }
+        System::assert_has_event(Event::VoteEnded(TARGET, VoteResult::Rejected).into());
+        System::assert_last_event(Event::AccountUnlocked(TARGET).into());
+        assert!(CouncilLock::locked_accounts(TARGET).is_none());
+        assert!(CouncilLock::referendums(TARGET).is_none());
+        assert_eq!(
+            CouncilLock::resolutions((2, TARGET)).unwrap().result,
+            VoteResult::Rejected
+        );
+        assert!(can_transfer(TARGET));
+    });
+}
+
+#[test]
+fn only_root_or_the_admin_manages_the_pallet() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CouncilLock::set_proposal_fee(RawOrigin::Signed(1).into(), 5),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(CouncilLock::set_pallet_admin(RawOrigin::Root.into(), 1));
+        System::assert_last_event(Event::AdminChanged(None, 1).into());
+        assert_ok!(CouncilLock::set_proposal_fee(
+            RawOrigin::Signed(1).into(),
+            5
+        ));
+        assert_eq!(CouncilLock::proposal_fee(), 5);
+        assert_noop!(
+            CouncilLock::set_proposal_fee(RawOrigin::Signed(2).into(), 6),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            CouncilLock::set_pallet_admin(RawOrigin::Signed(2).into(), 2),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn a_proposed_admin_takes_over_after_the_delay() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CouncilLock::accept_pallet_admin(RawOrigin::Signed(2).into()),
+            Error::<Test>::NoPendingAdmin
+        );
+        assert_ok!(CouncilLock::set_pallet_admin(RawOrigin::Root.into(), 1));
+        assert_ok!(CouncilLock::set_pallet_admin(
+            RawOrigin::Signed(1).into(),
+            2
+        ));
+        System::assert_last_event(Event::AdminProposed(2, 3).into());
+        assert_noop!(
+            CouncilLock::accept_pallet_admin(RawOrigin::Signed(3).into()),
+            Error::<Test>::NotPendingAdmin
+        );
+        assert_noop!(
+            CouncilLock::accept_pallet_admin(RawOrigin::Signed(2).into()),
+            Error::<Test>::AdminTimelockActive
+        );
+        System::set_block_number(3);
+        assert_ok!(CouncilLock::accept_pallet_admin(
+            RawOrigin::Signed(2).into()
+        ));
+        System::assert_last_event(Event::AdminChanged(Some(1), 2).into());
+        assert_eq!(CouncilLock::pallet_admin(), Some(2));
+        assert!(CouncilLock::pending_pallet_admin().is_none());
+    });
+}
+
+#[test]
+fn root_or_the_admin_cancels_a_proposed_admin() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CouncilLock::set_pallet_admin(RawOrigin::Root.into(), 1));
+        assert_ok!(CouncilLock::set_pallet_admin(
+            RawOrigin::Signed(1).into(),
+            2
+        ));
+        assert_noop!(
+            CouncilLock::cancel_pallet_admin(RawOrigin::Signed(2).into()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(CouncilLock::cancel_pallet_admin(
+            RawOrigin::Signed(1).into()
+        ));
+        System::assert_last_event(Event::AdminProposalCancelled(2).into());
+        System::set_block_number(3);
+        assert_noop!(
+            CouncilLock::accept_pallet_admin(RawOrigin::Signed(2).into()),
+            Error::<Test>::NoPendingAdmin
+        );
+        assert_ok!(CouncilLock::set_pallet_admin(
+            RawOrigin::Signed(1).into(),
+            2
+        ));
+        assert_ok!(CouncilLock::cancel_pallet_admin(RawOrigin::Root.into()));
+        assert!(CouncilLock::pending_pallet_admin().is_none());
+        assert_eq!(CouncilLock::pallet_admin(), Some(1));
+    });
+}
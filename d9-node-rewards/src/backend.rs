@@ -0,0 +1,135 @@
+use crate::pallet::{Config, Error, Event, Pallet, PendingRewards, TotalPendingRewards};
+use crate::{NodeTier, RewardBalanceOf, RewardSource};
+use frame_support::{dispatch::DispatchClass, inherent::Vec, pallet_prelude::*, weights::Weight};
+use pallet_d9_node_voting::{NodeAccumulativeVotes, NodeToUserVotesTotals};
+use sp_runtime::{traits::Zero, PerThing, Perbill, Percent, Saturating};
+use sp_staking::SessionIndex;
+
+/// issues the rewards of an ended session
+pub trait RewardBackend<AccountId, Balance> {
+    /// the rewards issued natively, `None` if they are paid outside the pallet
+    fn issue_rewards(
+        end_index: SessionIndex,
+        nodes: &[(AccountId, u64, NodeTier)],
+    ) -> Result<Option<Balance>, DispatchError>;
+}
+
+/// who backs a node, and how much of its rewards they get
+pub trait NodeSupportProvider<AccountId> {
+    /// percent of a node's rewards passed on to its supporters
+    fn sharing_percent(node: &AccountId) -> Option<u8>;
+    /// each supporter of `node` with its share of the node's votes. the node's own self-stake is
+    /// not a supporter, its share stays with the node.
+    fn supporters(node: &AccountId) -> Vec<(AccountId, Perbill)>;
+}
+
+impl<T: pallet_d9_node_voting::Config> NodeSupportProvider<T::AccountId>
+    for pallet_d9_node_voting::Pallet<T>
+{
+    fn sharing_percent(node: &T::AccountId) -> Option<u8> {
+        Self::get_node_sharing_percent(node.clone())
+    }
+
+    fn supporters(node: &T::AccountId) -> Vec<(T::AccountId, Perbill)> {
+        let node_votes = match NodeAccumulativeVotes::<T>::get(node) {
+            Some(votes) if votes > 0 => votes,
+            _ => return Vec::new(),
+        };
+        NodeToUserVotesTotals::<T>::iter_prefix((node.clone(),))
+            .filter(|(supporter, _)| supporter != node)
+            .map(|(supporter, votes)| {
+                let votes = Self::vote_retention(&supporter).mul_floor(votes);
+                (supporter, Perbill::from_rational(votes, node_votes))
+            })
+            .collect()
+    }
+}
+
+/// sends the ranking to the node reward contract
+///
+/// the gas the call consumes, at most `Pallet::max_contract_gas_limit`, is registered as
+/// mandatory weight.
+pub struct ContractBackend<T>(PhantomData<T>);
+impl<T: Config> RewardBackend<T::AccountId, RewardBalanceOf<T>> for ContractBackend<T> {
+    fn issue_rewards(
+        end_index: SessionIndex,
+        nodes: &[(T::AccountId, u64, NodeTier)],
+    ) -> Result<Option<RewardBalanceOf<T>>, DispatchError> {
+        let sorted_nodes = nodes
+            .iter()
+            .map(|(node, votes, _)| (node.clone(), *votes))
+            .collect();
+        Pallet::<T>::update_rewards_on_contract(end_index, sorted_nodes)?;
+        Ok(None)
+    }
+}
+
+/// splits `SessionRewards` between the nodes by tier weight, and each node's part between the
+/// node and its supporters by its `sharing_percent`. rewards accrue until claimed.
+///
+/// paying from the pot, a session is only issued once the pot holds its rewards on top of those
+/// already pending, otherwise it fails and is retried.
+///
+/// the work grows with the ranked nodes and their supporters, which node-voting bounds by
+/// `MaxCandidates` and `MaxSupportersPerCandidate`. it is registered as mandatory weight since
+/// rewards are issued from the session hooks.
+pub struct NativeBackend<T>(PhantomData<T>);
+impl<T: Config> RewardBackend<T::AccountId, RewardBalanceOf<T>> for NativeBackend<T> {
+    fn issue_rewards(
+        end_index: SessionIndex,
+        nodes: &[(T::AccountId, u64, NodeTier)],
+    ) -> Result<Option<RewardBalanceOf<T>>, DispatchError> {
+        let session_rewards = T::SessionRewards::get();
+        if T::RewardSource::get() == RewardSource::Pot {
+            ensure!(
+                Pallet::<T>::uncommitted_pot() >= session_rewards,
+                Error::<T>::InsufficientRewardPot
+            );
+        }
+        let mut issued: RewardBalanceOf<T> = Zero::zero();
+        let mut rewarded_nodes = 0u64;
+        let mut credited_supporters = 0u64;
+        for (node, share) in Pallet::<T>::reward_shares(nodes) {
+            let node_reward = share * session_rewards;
+            if node_reward.is_zero() {
+                continue;
+            }
+            rewarded_nodes = rewarded_nodes.saturating_add(1);
+            let sharing_percent = T::NodeSupport::sharing_percent(&node).unwrap_or(0);
+            let supporter_rewards = Percent::from_percent(sharing_percent) * node_reward;
+            let mut paid_to_supporters: RewardBalanceOf<T> = Zero::zero();
+            for (supporter, support_ratio) in T::NodeSupport::supporters(&node) {
+                let reward = support_ratio * supporter_rewards;
+                credit::<T>(&supporter, reward);
+                credited_supporters = credited_supporters.saturating_add(1);
+                paid_to_supporters = paid_to_supporters.saturating_add(reward);
+            }
+            credit::<T>(&node, node_reward.saturating_sub(paid_to_supporters));
+            issued = issued.saturating_add(node_reward);
+        }
+        TotalPendingRewards::<T>::mutate(|total| *total = total.saturating_add(issued));
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            issue_weight::<T>(rewarded_nodes, credited_supporters),
+            DispatchClass::Mandatory,
+        );
+        Pallet::<T>::deposit_event(Event::RewardsIssued(end_index, issued));
+        Ok(Some(issued))
+    }
+}
+
+/// weight of natively rewarding `nodes` nodes and `supporters` of their supporters. the pot and
+/// the pending total are read and the total written, each node reads its sharing percent and
+/// votes and is credited, each supporter is read with its vote decay and credited.
+pub fn issue_weight<T: Config>(nodes: u64, supporters: u64) -> Weight {
+    T::DbWeight::get()
+        .reads_writes(4, 1)
+        .saturating_add(T::DbWeight::get().reads_writes(3, 1).saturating_mul(nodes))
+        .saturating_add(T::DbWeight::get().reads_writes(3, 1).saturating_mul(supporters))
+}
+
+fn credit<T: Config>(who: &T::AccountId, amount: RewardBalanceOf<T>) {
+    if amount.is_zero() {
+        return;
+    }
+    PendingRewards::<T>::mutate(who, |pending| *pending = pending.saturating_add(amount));
+}
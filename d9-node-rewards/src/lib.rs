@@ -1,28 +1,55 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_staking::SessionIndex;
+mod backend;
+pub mod migration;
+mod runtime_api;
 mod structs;
+#[cfg(test)]
+mod tests;
+pub use backend::*;
+pub use runtime_api::*;
+pub use structs::*;
 use frame_support::{traits::Currency, PalletId};
 pub use pallet::*;
 
 pub type BalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
     <T as frame_system::Config>::AccountId,
 >>::Balance;
+pub type RewardBalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+pub type PalletAdminHandover<T> = d9_admin::AdminHandover<
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::BlockNumber,
+    PalletAdmin<T>,
+    PendingPalletAdmin<T>,
+>;
+/// ref time node reward contract calls were given before the gas limit was configurable. the
+/// default gas limit is this, capped by `Pallet::max_contract_gas_limit`.
+pub const DEFAULT_CONTRACT_GAS_LIMIT: u64 = 2_000_000_000_000;
+
+/// root or the pallet admin
+pub type EnsurePalletAdmin<T> =
+    d9_admin::EnsureRootOrAdmin<<T as frame_system::Config>::AccountId, PalletAdmin<T>>;
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
+    use d9_admin::{AdminChange, AdminError, AdminErrors, PendingAdmin};
     use frame_support::{
         inherent::Vec,
-        pallet_prelude::{DispatchResult, OptionQuery, *},
+        pallet_prelude::{DispatchResult, OptionQuery, ValueQuery, *},
+        traits::{ExistenceRequirement, Imbalance},
         weights::Weight,
     };
     use frame_system::pallet_prelude::*;
     use pallet_d9_node_voting::NodeRewardManager;
     use sp_runtime::traits::AccountIdConversion;
-    use sp_runtime::traits::BadOrigin;
+    use sp_runtime::traits::{BadOrigin, Zero};
+    use sp_runtime::{Perbill, Saturating};
     const STORAGE_VERSION: frame_support::traits::StorageVersion =
-        frame_support::traits::StorageVersion::new(1);
+        frame_support::traits::StorageVersion::new(2);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -38,21 +65,135 @@ pub mod pallet {
 
         #[pallet::constant]
         type PalletId: Get<PalletId>;
+
+        /// share of the session rewards each node tier is weighted with
+        type TierRewardWeights: Get<TierRewardWeights>;
+
+        /// issues each session's rewards, natively or through the node reward contract
+        type RewardBackend: RewardBackend<Self::AccountId, RewardBalanceOf<Self>>;
+
+        /// supporters of each node and the share of its rewards they get
+        type NodeSupport: NodeSupportProvider<Self::AccountId>;
+
+        /// rewards the native backend issues per session
+        type SessionRewards: Get<RewardBalanceOf<Self>>;
+
+        /// whether claimed native rewards are minted or paid from the pallet account
+        type RewardSource: Get<RewardSource>;
+
+        /// most nodes kept from a session whose rewards failed. a failed session with more nodes
+        /// is dropped rather than retried for part of them.
+        type MaxRewardedNodes: Get<u32>;
+
+        /// most failed sessions kept for retrying, the oldest is dropped beyond it
+        type MaxQueuedRewardUpdates: Get<u32>;
+
+        /// blocks before the first retry, doubled after every failed retry
+        type RetryBackoff: Get<Self::BlockNumber>;
+
+        /// retries after which a failed session is given up
+        type MaxRetryAttempts: Get<u32>;
+
+        /// number of past sessions kept in `RewardHistory`, must be at least one
+        type RewardHistoryDepth: Get<SessionIndex>;
+
+        /// timelock on handing over the pallet admin role, see `d9_admin`
+        type AdminTransferDelay: Get<Self::BlockNumber>;
     }
 
     #[pallet::storage]
     #[pallet::getter(fn node_reward_contract)]
     pub type NodeRewardContract<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    /// native rewards issued to each account and not yet claimed
+    #[pallet::storage]
+    #[pallet::getter(fn pending_rewards)]
+    pub type PendingRewards<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, RewardBalanceOf<T>, ValueQuery>;
+
+    /// sum of `PendingRewards`, which the pot keeps aside when it pays the rewards
+    #[pallet::storage]
+    #[pallet::getter(fn total_pending_rewards)]
+    pub type TotalPendingRewards<T: Config> = StorageValue<_, RewardBalanceOf<T>, ValueQuery>;
+
+    /// sessions whose rewards failed, with the block of their next retry, oldest first
+    #[pallet::storage]
+    #[pallet::getter(fn reward_retry_queue)]
+    pub type RewardRetryQueue<T: Config> = StorageValue<
+        _,
+        BoundedVec<(SessionIndex, T::BlockNumber), T::MaxQueuedRewardUpdates>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn failed_reward_update)]
+    pub type FailedRewardUpdates<T: Config> =
+        StorageMap<_, Twox64Concat, SessionIndex, FailedRewardUpdate<T>, OptionQuery>;
+
+    /// what was submitted for the rewards of each of the last `RewardHistoryDepth` sessions
+    #[pallet::storage]
+    #[pallet::getter(fn reward_history)]
+    pub type RewardHistory<T: Config> =
+        StorageMap<_, Twox64Concat, SessionIndex, SessionRewardRecord<T>, OptionQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultContractCallConfig<T: Config>() -> ContractCallConfig<BalanceOf<T>> {
+        ContractCallConfig {
+            //0x93440f8d
+            //update_rewards
+            selector: [0x93, 0x44, 0x0f, 0x8d],
+            gas_limit: Weight::from_parts(DEFAULT_CONTRACT_GAS_LIMIT, u64::MAX)
+                .min(Pallet::<T>::max_contract_gas_limit()),
+            storage_deposit_limit: None,
+        }
+    }
+
+    /// selector, gas limit and storage deposit limit of node reward contract calls
+    #[pallet::storage]
+    #[pallet::getter(fn contract_call_config)]
+    pub type ContractCallSettings<T: Config> = StorageValue<
+        _,
+        ContractCallConfig<BalanceOf<T>>,
+        ValueQuery,
+        DefaultContractCallConfig<T>,
+    >;
+
     #[pallet::storage]
     #[pallet::getter(fn pallet_admin)]
     pub type PalletAdmin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    /// successor proposed by the pallet admin, waiting to accept
+    #[pallet::storage]
+    #[pallet::getter(fn pending_pallet_admin)]
+    pub type PendingPalletAdmin<T: Config> =
+        StorageValue<_, PendingAdmin<T::AccountId, T::BlockNumber>, OptionQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         ErrorIssuingRewards,
         ContractError(DispatchError),
+        /// (session, total rewards issued natively)
+        RewardsIssued(SessionIndex, RewardBalanceOf<T>),
+        /// (account, amount claimed)
+        RewardsClaimed(T::AccountId, RewardBalanceOf<T>),
+        /// (session) queued for retrying
+        RewardUpdateQueued(SessionIndex),
+        /// (session, attempts)
+        RewardUpdateRetrySucceeded(SessionIndex, u32),
+        /// (session, attempts, block of the next retry)
+        RewardUpdateRetryFailed(SessionIndex, u32, T::BlockNumber),
+        /// (session) given up after `MaxRetryAttempts`
+        RewardUpdateAbandoned(SessionIndex),
+        /// (session) removed from the queue by an admin or to make room
+        RewardUpdateDropped(SessionIndex),
+        ContractCallConfigUpdated(ContractCallConfig<BalanceOf<T>>),
+        /// (proposed admin, first block it may accept)
+        AdminProposed(T::AccountId, T::BlockNumber),
+        /// (old admin, new admin)
+        AdminChanged(Option<T::AccountId>, T::AccountId),
+        /// (proposed admin) whose proposal was withdrawn
+        AdminProposalCancelled(T::AccountId),
     }
 
     #[pallet::error]
@@ -60,15 +201,80 @@ pub mod pallet {
         RestrictedAccess,
         NodeRewardContractNotSet,
         ErrorUpdatingNodeRewardContract,
+        NoRewardsToClaim,
+        InsufficientRewardPot,
+        RewardBelowExistentialDeposit,
+        UnknownRewardUpdate,
+        GasLimitExceedsBlockWeight,
+        /// the node reward contract reverted the update
+        ContractReverted,
+        NoPendingAdmin,
+        NotPendingAdmin,
+        AdminTimelockActive,
+    }
+
+    impl<T: Config> AdminErrors for Error<T> {
+        const NO_PENDING_ADMIN: Self = Self::NoPendingAdmin;
+        const NOT_PENDING_ADMIN: Self = Self::NotPendingAdmin;
+        const TIMELOCK_ACTIVE: Self = Self::AdminTimelockActive;
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let mut used_weight = T::DbWeight::get().reads(1);
+            if remaining_weight.any_lt(used_weight) {
+                return Weight::zero();
+            }
+            let due_sessions = RewardRetryQueue::<T>::get()
+                .into_iter()
+                .filter(|(_, next_retry)| *next_retry <= now)
+                .map(|(session, _)| session)
+                .collect::<Vec<SessionIndex>>();
+            for session in due_sessions {
+                let retry_weight = Self::retry_weight();
+                if remaining_weight.any_lt(used_weight.saturating_add(retry_weight)) {
+                    break;
+                }
+                Self::do_retry_reward_update(session, now);
+                used_weight = used_weight.saturating_add(retry_weight);
+            }
+            used_weight
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::RewardHistoryDepth::get() > 0,
+                "a RewardHistoryDepth of zero prunes each session's record as it is written"
+            );
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
+        /// hand the pallet admin role to `new_admin`
+        ///
+        /// root sets the admin at once. the admin only proposes `new_admin`, who takes over with
+        /// `accept_pallet_admin` once `AdminTransferDelay` blocks have passed.
         #[pallet::call_index(0)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
         pub fn set_pallet_admin(origin: OriginFor<T>, new_admin: T::AccountId) -> DispatchResult {
-            Self::root_or_admin(origin)?;
-            PalletAdmin::<T>::put(new_admin);
+            let caller = ensure_signed_or_root(origin)?;
+            let change = PalletAdminHandover::<T>::propose(
+                caller,
+                new_admin,
+                frame_system::Pallet::<T>::block_number(),
+                T::AdminTransferDelay::get(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            match change {
+                AdminChange::Proposed(proposed, acceptable_at) => {
+                    Self::deposit_event(Event::AdminProposed(proposed, acceptable_at))
+                }
+                AdminChange::Changed(old_admin, new_admin) => {
+                    Self::deposit_event(Event::AdminChanged(old_admin, new_admin))
+                }
+            }
             Ok(())
         }
 
@@ -82,31 +288,288 @@ pub mod pallet {
             NodeRewardContract::<T>::put(new_contract);
             Ok(())
         }
+
+        /// pay out the caller's native rewards
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 4))]
+        pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
+            let claimer = ensure_signed(origin)?;
+            let amount = PendingRewards::<T>::get(&claimer);
+            ensure!(!amount.is_zero(), Error::<T>::NoRewardsToClaim);
+            match T::RewardSource::get() {
+                RewardSource::Mint => {
+                    let minted = <T as Config>::Currency::deposit_creating(&claimer, amount);
+                    ensure!(!minted.peek().is_zero(), Error::<T>::RewardBelowExistentialDeposit);
+                }
+                RewardSource::Pot => {
+                    <T as Config>::Currency::transfer(
+                        &Self::account_id(),
+                        &claimer,
+                        amount,
+                        ExistenceRequirement::KeepAlive,
+                    )
+                    .map_err(|_| Error::<T>::InsufficientRewardPot)?;
+                }
+            }
+            PendingRewards::<T>::remove(&claimer);
+            TotalPendingRewards::<T>::mutate(|total| *total = total.saturating_sub(amount));
+            Self::deposit_event(Event::RewardsClaimed(claimer, amount));
+            Ok(())
+        }
+
+        /// retry a failed session now, whatever its backoff
+        #[pallet::call_index(3)]
+        #[pallet::weight(Pallet::<T>::retry_weight())]
+        pub fn retry_reward_update(origin: OriginFor<T>, session: SessionIndex) -> DispatchResult {
+            Self::root_or_admin(origin)?;
+            ensure!(
+                FailedRewardUpdates::<T>::contains_key(session),
+                Error::<T>::UnknownRewardUpdate
+            );
+            Self::do_retry_reward_update(session, frame_system::Pallet::<T>::block_number());
+            Ok(())
+        }
+
+        /// give up on a failed session
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn drop_reward_update(origin: OriginFor<T>, session: SessionIndex) -> DispatchResult {
+            Self::root_or_admin(origin)?;
+            ensure!(
+                FailedRewardUpdates::<T>::contains_key(session),
+                Error::<T>::UnknownRewardUpdate
+            );
+            Self::set_reward_outcome(session, RewardOutcome::Dropped);
+            Self::remove_reward_update(session);
+            Self::deposit_event(Event::RewardUpdateDropped(session));
+            Ok(())
+        }
+
+        /// change how the node reward contract is called, e.g. after a contract upgrade
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_contract_call_config(
+            origin: OriginFor<T>,
+            config: ContractCallConfig<BalanceOf<T>>,
+        ) -> DispatchResult {
+            Self::root_or_admin(origin)?;
+            ensure!(
+                config.gas_limit.all_lte(Self::max_contract_gas_limit()),
+                Error::<T>::GasLimitExceedsBlockWeight
+            );
+            ContractCallSettings::<T>::put(config.clone());
+            Self::deposit_event(Event::ContractCallConfigUpdated(config));
+            Ok(())
+        }
+
+        /// take over the pallet admin role proposed to the caller
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn accept_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let new_admin = ensure_signed(origin)?;
+            let old_admin = PalletAdminHandover::<T>::accept(
+                new_admin.clone(),
+                frame_system::Pallet::<T>::block_number(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminChanged(old_admin, new_admin));
+            Ok(())
+        }
+
+        /// withdraw the pending pallet admin proposal, root or the admin
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn cancel_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let caller = ensure_signed_or_root(origin)?;
+            let proposed = PalletAdminHandover::<T>::cancel(caller)
+                .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminProposalCancelled(proposed));
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
         fn root_or_admin(origin: OriginFor<T>) -> Result<(), BadOrigin> {
-            let origin = ensure_signed_or_root(origin)?;
-            match origin {
-                Some(caller) => {
-                    let admin = PalletAdmin::<T>::get();
-                    if admin.is_some() && admin.unwrap() == caller {
-                        return Ok(());
+            EnsurePalletAdmin::<T>::ensure_origin(origin).map(|_| ())
+        }
+
+        /// `NodeRewardsApi::session_rewards`, the record of `session` while it is among the last
+        /// `RewardHistoryDepth` sessions. `NodeRewardsApi::pending_rewards` is the
+        /// `pending_rewards` getter
+        pub fn session_rewards(session: SessionIndex) -> Option<SessionRewardRecord<T>> {
+            RewardHistory::<T>::get(session)
+        }
+
+        /// each node's share of the session rewards, by the weight of its tier
+        pub fn reward_shares(
+            nodes: &[(T::AccountId, u64, NodeTier)],
+        ) -> Vec<(T::AccountId, Perbill)> {
+            let weights = T::TierRewardWeights::get();
+            let total_weight = nodes.iter().fold(0u64, |total, (_, _, tier)| {
+                total.saturating_add(weights.weight_of(tier).into())
+            });
+            nodes
+                .iter()
+                .map(|(node, _, tier)| {
+                    let share = if total_weight == 0 {
+                        Perbill::zero()
                     } else {
-                        return Err(BadOrigin);
-                    }
+                        Perbill::from_rational(u64::from(weights.weight_of(tier)), total_weight)
+                    };
+                    (node.clone(), share)
+                })
+                .collect()
+        }
+
+        /// weight of retrying one failed session
+        pub fn retry_weight() -> Weight {
+            Self::contract_gas_limit().saturating_add(T::DbWeight::get().reads_writes(5, 3))
+        }
+
+        fn contract_gas_limit() -> Weight {
+            ContractCallSettings::<T>::get()
+                .gas_limit
+                .min(Self::max_contract_gas_limit())
+        }
+
+        /// most gas a node reward contract call may use, a tenth of the block so retries fit
+        /// in `on_idle`
+        pub fn max_contract_gas_limit() -> Weight {
+            T::BlockWeights::get().max_block / 10
+        }
+
+        /// keep the nodes of a failed session for retrying after `RetryBackoff`
+        fn queue_reward_update(end_index: SessionIndex, nodes: Vec<(T::AccountId, u64, NodeTier)>) {
+            let nodes = match BoundedVec::try_from(nodes) {
+                Ok(nodes) => nodes,
+                Err(nodes) => {
+                    log::error!(
+                        "session {} rewards not queued, {} nodes exceed MaxRewardedNodes",
+                        end_index,
+                        nodes.len()
+                    );
+                    Self::set_reward_outcome(end_index, RewardOutcome::Dropped);
+                    Self::deposit_event(Event::RewardUpdateDropped(end_index));
+                    return;
                 }
+            };
+            let next_retry =
+                frame_system::Pallet::<T>::block_number().saturating_add(T::RetryBackoff::get());
+            let mut queue = RewardRetryQueue::<T>::get();
+            if queue.len() as u32 >= T::MaxQueuedRewardUpdates::get() && !queue.is_empty() {
+                let (oldest, _) = queue.remove(0);
+                FailedRewardUpdates::<T>::remove(oldest);
+                Self::set_reward_outcome(oldest, RewardOutcome::Dropped);
+                Self::deposit_event(Event::RewardUpdateDropped(oldest));
+            }
+            if queue.try_push((end_index, next_retry)).is_err() {
+                Self::set_reward_outcome(end_index, RewardOutcome::Dropped);
+                Self::deposit_event(Event::RewardUpdateDropped(end_index));
+                return;
+            }
+            RewardRetryQueue::<T>::put(queue);
+            FailedRewardUpdates::<T>::insert(end_index, FailedRewardUpdate { nodes, attempts: 0 });
+            Self::deposit_event(Event::RewardUpdateQueued(end_index));
+        }
+
+        fn do_retry_reward_update(session: SessionIndex, now: T::BlockNumber) {
+            let mut update = match FailedRewardUpdates::<T>::get(session) {
+                Some(update) => update,
                 None => {
-                    return Ok(());
+                    Self::remove_reward_update(session);
+                    return;
                 }
+            };
+            update.attempts = update.attempts.saturating_add(1);
+            if let Ok(reward_pot) = T::RewardBackend::issue_rewards(session, &update.nodes) {
+                RewardHistory::<T>::mutate(session, |record| {
+                    if let Some(record) = record {
+                        record.outcome = RewardOutcome::IssuedOnRetry(update.attempts);
+                        record.reward_pot = reward_pot;
+                    }
+                });
+                Self::remove_reward_update(session);
+                Self::deposit_event(Event::RewardUpdateRetrySucceeded(session, update.attempts));
+                return;
+            }
+            if update.attempts >= T::MaxRetryAttempts::get() {
+                Self::set_reward_outcome(session, RewardOutcome::Abandoned);
+                Self::remove_reward_update(session);
+                Self::deposit_event(Event::RewardUpdateAbandoned(session));
+                return;
             }
+            let backoff =
+                T::RetryBackoff::get().saturating_mul((1u32 << update.attempts.min(16)).into());
+            let next_retry = now.saturating_add(backoff);
+            RewardRetryQueue::<T>::mutate(|queue| {
+                if let Some(entry) = queue.iter_mut().find(|(queued, _)| *queued == session) {
+                    entry.1 = next_retry;
+                }
+            });
+            let attempts = update.attempts;
+            FailedRewardUpdates::<T>::insert(session, update);
+            Self::deposit_event(Event::RewardUpdateRetryFailed(session, attempts, next_retry));
+        }
+
+        /// record the nodes sent for `end_index` and drop the record leaving the history depth
+        fn record_session_rewards(
+            end_index: SessionIndex,
+            nodes: &[(T::AccountId, u64, NodeTier)],
+            outcome: RewardOutcome,
+            reward_pot: Option<RewardBalanceOf<T>>,
+        ) {
+            let total_votes = nodes
+                .iter()
+                .fold(0u64, |total, (_, votes, _)| total.saturating_add(*votes));
+            let truncated = nodes.len() > T::MaxRewardedNodes::get() as usize;
+            if truncated {
+                log::error!(
+                    "session {} reward record keeps {} of {} nodes, MaxRewardedNodes is too low",
+                    end_index,
+                    T::MaxRewardedNodes::get(),
+                    nodes.len()
+                );
+            }
+            RewardHistory::<T>::insert(
+                end_index,
+                SessionRewardRecord {
+                    nodes: BoundedVec::truncate_from(nodes.to_vec()),
+                    total_votes,
+                    reward_pot,
+                    outcome,
+                    truncated,
+                },
+            );
+            if let Some(expired) = end_index.checked_sub(T::RewardHistoryDepth::get()) {
+                RewardHistory::<T>::remove(expired);
+            }
+        }
+
+        fn set_reward_outcome(session: SessionIndex, outcome: RewardOutcome) {
+            RewardHistory::<T>::mutate(session, |record| {
+                if let Some(record) = record {
+                    record.outcome = outcome;
+                }
+            });
+        }
+
+        fn remove_reward_update(session: SessionIndex) {
+            FailedRewardUpdates::<T>::remove(session);
+            RewardRetryQueue::<T>::mutate(|queue| queue.retain(|(queued, _)| *queued != session));
         }
 
         fn account_id() -> T::AccountId {
             T::PalletId::get().into_account_truncating()
         }
 
-        fn update_rewards_on_contract(
+        /// what the pot holds beyond its existential deposit and the rewards already pending
+        pub fn uncommitted_pot() -> RewardBalanceOf<T> {
+            <T as Config>::Currency::free_balance(&Self::account_id())
+                .saturating_sub(<T as Config>::Currency::minimum_balance())
+                .saturating_sub(TotalPendingRewards::<T>::get())
+        }
+
+        pub(crate) fn update_rewards_on_contract(
             end_index: SessionIndex,
             sorted_nodes: Vec<(T::AccountId, u64)>,
         ) -> Result<(), Error<T>> {
@@ -115,10 +578,8 @@ pub mod pallet {
             //     .cloned() // Create owned copies
             //     .collect::<Vec<T::AccountId>>();
 
-            //0x93440f8d
-            //0x93440f8d
-            //update_rewards
-            let mut selector: Vec<u8> = [0x93, 0x44, 0x0f, 0x8d].into();
+            let call_config = ContractCallSettings::<T>::get();
+            let mut selector: Vec<u8> = call_config.selector.into();
             let mut encoded_index = (end_index as u32).encode();
             let mut encoded_nodes: Vec<u8> = sorted_nodes.encode();
             let mut data_for_contract_call = Vec::new();
@@ -132,20 +593,28 @@ pub mod pallet {
             }
 
             let node_reward_contract = node_reward_contract_opt.unwrap();
-            let weight: Weight = Weight::from_parts(2_000_000_000_000, u64::MAX);
+            let weight: Weight = Self::contract_gas_limit();
             let send_value: BalanceOf<T> = (0u32).into();
-            let contract_call_result = pallet_contracts::Pallet::<T>::bare_call(
+            let contract_call = pallet_contracts::Pallet::<T>::bare_call(
                 Self::account_id(),
                 node_reward_contract,
                 send_value,
                 weight,
-                None,
+                call_config.storage_deposit_limit,
                 data_for_contract_call,
                 false,
                 pallet_contracts::Determinism::Enforced,
-            )
-            .result;
-            match contract_call_result {
+            );
+            // rewards are updated from the session hooks, outside any extrinsic's weight
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                contract_call.gas_consumed,
+                DispatchClass::Mandatory,
+            );
+            match contract_call.result {
+                Ok(ret) if ret.did_revert() => {
+                    Self::deposit_event(Event::ContractError(Error::<T>::ContractReverted.into()));
+                    Err(Error::<T>::ErrorUpdatingNodeRewardContract)
+                }
                 Ok(_) => Ok(()),
                 Err(err) => {
                     Self::deposit_event(Event::ContractError(err));
@@ -159,14 +628,28 @@ pub mod pallet {
         /// pull data to update the pool
         fn update_rewards(
             end_index: SessionIndex,
-            sorted_node_list: Vec<(T::AccountId, u64)>,
+            sorted_node_list: Vec<(T::AccountId, u64, NodeTier)>,
         ) -> () {
-            let contract_update_result =
-                Self::update_rewards_on_contract(end_index, sorted_node_list);
-            if contract_update_result.is_err() {
-                Self::deposit_event(Event::ErrorIssuingRewards);
-                return;
-            }
+            let reward_pot = match T::RewardBackend::issue_rewards(end_index, &sorted_node_list) {
+                Ok(reward_pot) => reward_pot,
+                Err(_) => {
+                    Self::record_session_rewards(
+                        end_index,
+                        &sorted_node_list,
+                        RewardOutcome::Failed,
+                        None,
+                    );
+                    Self::deposit_event(Event::ErrorIssuingRewards);
+                    Self::queue_reward_update(end_index, sorted_node_list);
+                    return;
+                }
+            };
+            Self::record_session_rewards(
+                end_index,
+                &sorted_node_list,
+                RewardOutcome::Issued,
+                reward_pot,
+            );
         }
     }
 }
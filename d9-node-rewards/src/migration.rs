@@ -0,0 +1,33 @@
+use super::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+    weights::Weight,
+};
+
+/// stores the node reward contract call settings, which used to be hard-coded (storage version
+/// 1 to 2)
+///
+/// calls used to get `DEFAULT_CONTRACT_GAS_LIMIT` ref time. gas limits are now capped at a tenth
+/// of the block weight so failed calls can be retried in `on_idle`. the old limit is stored where
+/// it is within the cap, otherwise the cap is stored and the lower limit is logged.
+pub struct MigrateToContractCallSettings<T>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToContractCallSettings<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() != 1 {
+            return T::DbWeight::get().reads(1);
+        }
+        let previous_gas_limit = Weight::from_parts(DEFAULT_CONTRACT_GAS_LIMIT, u64::MAX);
+        let config = DefaultContractCallConfig::<T>::get();
+        if config.gas_limit.ref_time() < previous_gas_limit.ref_time() {
+            log::warn!(
+                "node reward contract gas limit lowered from {} to {} ref time, a tenth of the block weight",
+                previous_gas_limit.ref_time(),
+                config.gas_limit.ref_time()
+            );
+        }
+        ContractCallSettings::<T>::put(config);
+        StorageVersion::new(2).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(1, 2)
+    }
+}
@@ -0,0 +1,20 @@
+use codec::Codec;
+use sp_staking::SessionIndex;
+
+sp_api::decl_runtime_apis! {
+    /// node reward records for operators and frontends
+    ///
+    /// `Record` is the runtime's `SessionRewardRecord`. runtimes answer with
+    /// `Pallet::session_rewards` and `Pallet::pending_rewards`
+    pub trait NodeRewardsApi<AccountId, Balance, Record>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        Record: Codec,
+    {
+        /// what was submitted for the rewards of `session`, if still kept
+        fn session_rewards(session: SessionIndex) -> Option<Record>;
+        /// native rewards `account` can claim
+        fn pending_rewards(account: AccountId) -> Balance;
+    }
+}
@@ -1,37 +1,141 @@
-use frame_support::pallet_prelude::*;
+use crate::{ pallet::Config, RewardBalanceOf };
+use frame_support::{ pallet_prelude::*, weights::Weight, RuntimeDebugNoBound };
 use codec::MaxEncodedLen;
+pub use pallet_d9_node_voting::{ NodeTier, SuperNodeSubTier };
+
+/// relative reward weight of each `NodeTier`
 #[derive(
     PartialEq,
     Eq,
-    PartialOrd,
-    Ord,
     Clone,
+    Default,
     Encode,
     Decode,
     RuntimeDebug,
     TypeInfo,
     MaxEncodedLen
 )]
-pub enum NodeTier {
-    Super(SuperNodeSubTier),
-    StandBy,
-    Candidate,
+pub struct TierRewardWeights {
+    pub super_upper: u32,
+    pub super_middle: u32,
+    pub super_lower: u32,
+    pub standby: u32,
+    pub candidate: u32,
+}
+
+impl TierRewardWeights {
+    pub fn weight_of(&self, tier: &NodeTier) -> u32 {
+        match tier {
+            NodeTier::Super(SuperNodeSubTier::Upper) => self.super_upper,
+            NodeTier::Super(SuperNodeSubTier::Middle) => self.super_middle,
+            NodeTier::Super(SuperNodeSubTier::Lower) => self.super_lower,
+            NodeTier::StandBy => self.standby,
+            NodeTier::Candidate => self.candidate,
+        }
+    }
 }
 
+/// where claimed native rewards come from
 #[derive(
     PartialEq,
     Eq,
-    PartialOrd,
-    Ord,
     Clone,
+    Copy,
     Encode,
     Decode,
     RuntimeDebug,
     TypeInfo,
     MaxEncodedLen
 )]
-pub enum SuperNodeSubTier {
-    Upper,
-    Middle,
-    Lower,
+pub enum RewardSource {
+    /// newly minted, raising total issuance
+    Mint,
+    /// paid from the pallet account
+    Pot,
+}
+
+/// a session whose rewards could not be issued, kept for retrying
+#[derive(
+    PartialEqNoBound,
+    EqNoBound,
+    CloneNoBound,
+    Encode,
+    Decode,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen
+)]
+#[scale_info(skip_type_params(T))]
+pub struct FailedRewardUpdate<T: Config> {
+    pub nodes: BoundedVec<(T::AccountId, u64, NodeTier), T::MaxRewardedNodes>,
+    /// retries made so far
+    pub attempts: u32,
+}
+
+/// how the node reward contract is called
+#[derive(
+    PartialEq,
+    Eq,
+    Clone,
+    Encode,
+    Decode,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen
+)]
+pub struct ContractCallConfig<Balance> {
+    /// selector of the contract's `update_rewards` message
+    pub selector: [u8; 4],
+    /// most gas a call may use, at most a tenth of the block weight limit
+    pub gas_limit: Weight,
+    /// most storage deposit a call may take, unlimited if `None`
+    pub storage_deposit_limit: Option<Balance>,
+}
+
+/// what became of a session's rewards
+#[derive(
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Encode,
+    Decode,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen
+)]
+pub enum RewardOutcome {
+    Issued,
+    /// failed and queued for retrying
+    Failed,
+    /// issued by the retry with this number
+    IssuedOnRetry(u32),
+    /// given up after `MaxRetryAttempts`
+    Abandoned,
+    /// removed from the retry queue before it succeeded
+    Dropped,
+}
+
+/// what was submitted for a session's rewards
+#[derive(
+    PartialEqNoBound,
+    EqNoBound,
+    CloneNoBound,
+    Encode,
+    Decode,
+    RuntimeDebugNoBound,
+    TypeInfo,
+    MaxEncodedLen
+)]
+#[scale_info(skip_type_params(T))]
+pub struct SessionRewardRecord<T: Config> {
+    /// ranked nodes sent, with their votes and tiers
+    pub nodes: BoundedVec<(T::AccountId, u64, NodeTier), T::MaxRewardedNodes>,
+    /// votes of every node sent, including any left out of `nodes`
+    pub total_votes: u64,
+    /// rewards issued natively, `None` if the contract paid them or nothing was issued yet
+    pub reward_pot: Option<RewardBalanceOf<T>>,
+    pub outcome: RewardOutcome,
+    /// more than `MaxRewardedNodes` nodes were sent and `nodes` keeps only the first of them
+    pub truncated: bool,
 }
@@ -0,0 +1,277 @@
+use crate as pallet_d9_node_rewards;
+use crate::*;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstBool, ConstU32, ConstU64, LockIdentifier, Randomness, Time},
+    weights::{constants::RocksDbWeight, Weight},
+    PalletId,
+};
+use frame_system::EnsureRoot;
+use pallet_d9_node_voting::{
+    ConsecutiveUnderperformance, NativeBurnHandler, TopVotes, VotingInterestMode,
+};
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, Convert, IdentityLookup},
+    DispatchError,
+};
+
+pub type AccountId = u64;
+type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
+        NodeVoting: pallet_d9_node_voting::{Pallet, Call, Storage, Event<T>},
+        NodeRewards: pallet_d9_node_rewards::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub BlockWeights: frame_system::limits::BlockWeights =
+        frame_system::limits::BlockWeights::simple_max(
+            Weight::from_parts(2_000_000_000_000, u64::MAX),
+        );
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = BlockWeights;
+    type BlockLength = ();
+    type DbWeight = RocksDbWeight;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ConstU32<10>;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type MaxHolds = ();
+    type FreezeIdentifier = ();
+    type HoldIdentifier = ();
+    type MaxFreezes = ();
+}
+
+/// contracts only need a clock and randomness to exist
+pub struct TestTime;
+impl Time for TestTime {
+    type Moment = u64;
+
+    fn now() -> u64 {
+        0
+    }
+}
+
+pub struct TestRandomness;
+impl Randomness<H256, u64> for TestRandomness {
+    fn random(_: &[u8]) -> (H256, u64) {
+        (H256::zero(), 0)
+    }
+}
+
+impl Convert<Weight, u64> for Test {
+    fn convert(weight: Weight) -> u64 {
+        weight.ref_time()
+    }
+}
+
+parameter_types! {
+    pub ContractSchedule: pallet_contracts::Schedule<Test> = Default::default();
+}
+
+impl pallet_contracts::Config for Test {
+    type Time = TestTime;
+    type Randomness = TestRandomness;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type CallFilter = frame_support::traits::Nothing;
+    type WeightPrice = Self;
+    type WeightInfo = ();
+    type ChainExtension = ();
+    type Schedule = ContractSchedule;
+    type CallStack = [pallet_contracts::Frame<Self>; 5];
+    type DepositPerByte = ConstU64<1>;
+    type DepositPerItem = ConstU64<1>;
+    type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+    type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+    type MaxStorageKeyLen = ConstU32<128>;
+    type UnsafeUnstableInterface = ConstBool<false>;
+    type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+}
+
+parameter_types! {
+    pub const VotingLockIdentifier: LockIdentifier = *b"d9voting";
+    pub const Mode: VotingInterestMode = VotingInterestMode::Burn;
+}
+
+impl pallet_d9_node_voting::Config for Test {
+    type CurrencySubUnits = ConstU64<1>;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type MaxCandidates = ConstU32<10>;
+    type MaxValidatorNodes = ConstU32<2>;
+    type NodeRewardManager = NodeRewards;
+    type ReferendumManager = ();
+    type BurnHandler = NativeBurnHandler<Test>;
+    type PruneReapedAccounts = ConstBool<true>;
+    type LockableCurrency = Balances;
+    type VotingLockIdentifier = VotingLockIdentifier;
+    type VotingInterestMode = Mode;
+    type UnbondingPeriod = ConstU32<2>;
+    type MaxUnlockingChunks = ConstU32<4>;
+    type CandidacyBond = ConstU64<100>;
+    type CandidacyBondUnbondingPeriod = ConstU32<2>;
+    type MaxSupportersPerCandidate = ConstU32<3>;
+    type MaxCleanupPerBlock = ConstU32<2>;
+    type EvictionCount = ConstU32<1>;
+    type CandidateGracePeriod = ConstU32<2>;
+    type MinimumCandidateVotes = ConstU64<0>;
+    type SessionHistoryDepth = ConstU32<2>;
+    type VoteDecayRate = ();
+    type ElectionStrategy = TopVotes<Test>;
+    type ValidatorPerformanceProvider = ();
+    type DemotionRule = ConsecutiveUnderperformance<ConstU32<5>, ConstU32<2>>;
+    type StandbyPeriod = ConstU32<2>;
+    type SharingPercentNoticePeriod = ConstU32<2>;
+    type MaxStandbyNodes = ConstU32<1>;
+    type MinimumSelfStake = ConstU64<0>;
+    type AllowVotingInterestTransfers = ConstBool<false>;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type OnCandidacyBondSlashed = ();
+    type AdminTransferDelay = ConstU64<2>;
+}
+
+parameter_types! {
+    pub const RewardsPalletId: PalletId = PalletId(*b"d9/nrwds");
+    pub static SessionRewards: u64 = 1_000;
+    pub static Source: RewardSource = RewardSource::Mint;
+    pub static UseContract: bool = false;
+    pub static Weights: TierRewardWeights = TierRewardWeights {
+        super_upper: 3,
+        super_middle: 2,
+        super_lower: 2,
+        standby: 1,
+        candidate: 1,
+    };
+}
+
+/// no node reward contract is set, so the contract backend always fails
+pub struct TestBackend;
+impl RewardBackend<AccountId, u64> for TestBackend {
+    fn issue_rewards(
+        end_index: SessionIndex,
+        nodes: &[(AccountId, u64, NodeTier)],
+    ) -> Result<Option<u64>, DispatchError> {
+        if UseContract::get() {
+            ContractBackend::<Test>::issue_rewards(end_index, nodes)
+        } else {
+            NativeBackend::<Test>::issue_rewards(end_index, nodes)
+        }
+    }
+}
+
+impl pallet_d9_node_rewards::Config for Test {
+    type CurrencySubUnits = ConstU64<1>;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PalletId = RewardsPalletId;
+    type TierRewardWeights = Weights;
+    type RewardBackend = TestBackend;
+    type NodeSupport = NodeVoting;
+    type SessionRewards = SessionRewards;
+    type RewardSource = Source;
+    type MaxRewardedNodes = ConstU32<4>;
+    type MaxQueuedRewardUpdates = ConstU32<2>;
+    type RetryBackoff = ConstU64<5>;
+    type MaxRetryAttempts = ConstU32<3>;
+    type RewardHistoryDepth = ConstU32<3>;
+    type AdminTransferDelay = ConstU64<2>;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=20).map(|account| (account, 10_000)).collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// a node reward contract that reverts every call
+const REVERTING_CONTRACT: &str = r#"
+(module
+    (import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+    (import "env" "memory" (memory 1 1))
+    (func (export "deploy"))
+    (func (export "call")
+        (call $seal_return (i32.const 1) (i32.const 0) (i32.const 0))
+    )
+)
+"#;
+
+/// instantiates `REVERTING_CONTRACT` from account 20 and makes it the node reward contract
+pub fn set_reverting_contract() -> AccountId {
+    let code = wat::parse_str(REVERTING_CONTRACT).unwrap();
+    frame_support::assert_ok!(Contracts::instantiate_with_code(
+        RuntimeOrigin::signed(20),
+        0,
+        Weight::from_parts(100_000_000_000, 1024 * 1024),
+        None,
+        code,
+        Vec::new(),
+        Vec::new(),
+    ));
+    let contract = System::events()
+        .into_iter()
+        .find_map(|record| match record.event {
+            RuntimeEvent::Contracts(pallet_contracts::Event::Instantiated { contract, .. }) => {
+                Some(contract)
+            }
+            _ => None,
+        })
+        .unwrap();
+    frame_support::assert_ok!(NodeRewards::set_node_reward_contract(
+        RuntimeOrigin::root(),
+        contract
+    ));
+    contract
+}
@@ -0,0 +1,396 @@
+use super::mock::*;
+use crate::*;
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::DispatchClass,
+    traits::{Currency as _, Hooks},
+    weights::Weight,
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_d9_node_voting::{NodeMetadataStruct, NodeRewardManager, ValidatorDelegations};
+use sp_runtime::{traits::AccountIdConversion, Perbill};
+
+const NODE: AccountId = 1;
+const SUPPORTER: AccountId = 2;
+const OTHER_NODE: AccountId = 3;
+const UPPER: NodeTier = NodeTier::Super(SuperNodeSubTier::Upper);
+
+/// NODE passes half its rewards on, stakes 100 votes on itself and is backed by 300 votes of
+/// SUPPORTER
+fn setup_supported_node() {
+    assert_ok!(NodeVoting::submit_candidacy(
+        RawOrigin::Signed(NODE).into(),
+        NodeMetadataStruct {
+            sharing_percent: 50,
+            ..Default::default()
+        },
+    ));
+    NodeVoting::add_voting_interest_internal(NODE, 100);
+    assert_ok!(NodeVoting::declare_self_stake(
+        RawOrigin::Signed(NODE).into(),
+        100
+    ));
+    NodeVoting::add_voting_interest_internal(SUPPORTER, 300);
+    assert_ok!(NodeVoting::delegate_votes(
+        RawOrigin::Signed(SUPPORTER).into(),
+        vec![ValidatorDelegations {
+            candidate: NODE,
+            votes: 300,
+        }],
+    ));
+}
+
+fn pot_account() -> AccountId {
+    RewardsPalletId::get().into_account_truncating()
+}
+
+#[test]
+fn supporters_leave_out_the_nodes_self_stake() {
+    new_test_ext().execute_with(|| {
+        setup_supported_node();
+        assert_eq!(
+            <NodeVoting as NodeSupportProvider<AccountId>>::supporters(&NODE),
+            vec![(SUPPORTER, Perbill::from_percent(75))]
+        );
+    });
+}
+
+#[test]
+fn native_rewards_are_split_between_the_node_and_its_supporters() {
+    new_test_ext().execute_with(|| {
+        setup_supported_node();
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        // half of the 1000 goes to supporters, SUPPORTER holds 300 of the 400 votes
+        assert_eq!(NodeRewards::pending_rewards(SUPPORTER), 375);
+        assert_eq!(NodeRewards::pending_rewards(NODE), 625);
+        System::assert_last_event(Event::RewardsIssued(1, 1_000).into());
+        // one node and one supporter credited in the session hook
+        assert_eq!(
+            *System::block_weight().get(DispatchClass::Mandatory),
+            issue_weight::<Test>(1, 1)
+        );
+    });
+}
+
+#[test]
+fn session_rewards_are_split_by_tier_weight() {
+    new_test_ext().execute_with(|| {
+        NodeRewards::update_rewards(
+            1,
+            vec![(NODE, 300, UPPER), (OTHER_NODE, 100, NodeTier::StandBy)],
+        );
+        assert_eq!(NodeRewards::pending_rewards(NODE), 750);
+        assert_eq!(NodeRewards::pending_rewards(OTHER_NODE), 250);
+    });
+}
+
+#[test]
+fn claiming_minted_rewards_raises_issuance() {
+    new_test_ext().execute_with(|| {
+        Source::set(RewardSource::Mint);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        let issuance = Balances::total_issuance();
+        assert_ok!(NodeRewards::claim_rewards(RawOrigin::Signed(NODE).into()));
+        assert_eq!(Balances::free_balance(NODE), 11_000);
+        assert_eq!(Balances::total_issuance(), issuance + 1_000);
+        assert_eq!(NodeRewards::pending_rewards(NODE), 0);
+        System::assert_last_event(Event::RewardsClaimed(NODE, 1_000).into());
+    });
+}
+
+#[test]
+fn claiming_from_the_pot_moves_funds_from_the_pallet_account() {
+    new_test_ext().execute_with(|| {
+        Source::set(RewardSource::Pot);
+        Balances::make_free_balance_be(&pot_account(), 5_000);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        let issuance = Balances::total_issuance();
+        assert_ok!(NodeRewards::claim_rewards(RawOrigin::Signed(NODE).into()));
+        assert_eq!(Balances::free_balance(NODE), 11_000);
+        assert_eq!(Balances::free_balance(pot_account()), 4_000);
+        assert_eq!(Balances::total_issuance(), issuance);
+    });
+}
+
+#[test]
+fn sessions_the_pot_cannot_cover_are_retried() {
+    new_test_ext().execute_with(|| {
+        Source::set(RewardSource::Pot);
+        Balances::make_free_balance_be(&pot_account(), 1_500);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        assert_eq!(NodeRewards::total_pending_rewards(), 1_000);
+        // 1_000 is set aside for session 1 and one unit is the existential deposit
+        NodeRewards::update_rewards(2, vec![(NODE, 400, UPPER)]);
+        System::assert_last_event(Event::RewardUpdateQueued(2).into());
+        assert_eq!(NodeRewards::pending_rewards(NODE), 1_000);
+        assert_eq!(
+            NodeRewards::reward_history(2).map(|record| record.outcome),
+            Some(RewardOutcome::Failed)
+        );
+
+        Balances::make_free_balance_be(&pot_account(), 2_001);
+        <NodeRewards as Hooks<u64>>::on_idle(6, BlockWeights::get().max_block);
+        System::assert_last_event(Event::RewardUpdateRetrySucceeded(2, 1).into());
+        assert_eq!(NodeRewards::pending_rewards(NODE), 2_000);
+        assert_ok!(NodeRewards::claim_rewards(RawOrigin::Signed(NODE).into()));
+        assert_eq!(Balances::free_balance(pot_account()), 1);
+        assert_eq!(NodeRewards::total_pending_rewards(), 0);
+    });
+}
+
+#[test]
+fn claiming_without_rewards_fails() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            NodeRewards::claim_rewards(RawOrigin::Signed(NODE).into()),
+            Error::<Test>::NoRewardsToClaim
+        );
+    });
+}
+
+#[test]
+fn failed_rewards_are_retried_on_idle() {
+    new_test_ext().execute_with(|| {
+        UseContract::set(true);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        System::assert_last_event(Event::RewardUpdateQueued(1).into());
+        assert_eq!(NodeRewards::reward_retry_queue().into_inner(), vec![(1, 6)]);
+        UseContract::set(false);
+        let max_block = BlockWeights::get().max_block;
+        // not due before the backoff, and not retried without room for the contract call
+        <NodeRewards as Hooks<u64>>::on_idle(5, max_block);
+        <NodeRewards as Hooks<u64>>::on_idle(6, Weight::from_parts(1, 1));
+        assert!(NodeRewards::failed_reward_update(1).is_some());
+        <NodeRewards as Hooks<u64>>::on_idle(6, max_block);
+        System::assert_last_event(Event::RewardUpdateRetrySucceeded(1, 1).into());
+        assert!(NodeRewards::failed_reward_update(1).is_none());
+        assert!(NodeRewards::reward_retry_queue().is_empty());
+        assert_eq!(NodeRewards::pending_rewards(NODE), 1_000);
+    });
+}
+
+#[test]
+fn reverted_contract_calls_are_queued_for_retry() {
+    new_test_ext().execute_with(|| {
+        set_reverting_contract();
+        UseContract::set(true);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        System::assert_has_event(
+            Event::ContractError(Error::<Test>::ContractReverted.into()).into(),
+        );
+        System::assert_last_event(Event::RewardUpdateQueued(1).into());
+        assert_eq!(NodeRewards::reward_retry_queue().into_inner(), vec![(1, 6)]);
+        assert_eq!(
+            NodeRewards::reward_history(1).map(|record| record.outcome),
+            Some(RewardOutcome::Failed)
+        );
+    });
+}
+
+#[test]
+fn contract_gas_is_registered_as_mandatory_weight() {
+    new_test_ext().execute_with(|| {
+        set_reverting_contract();
+        UseContract::set(true);
+        assert_eq!(
+            *System::block_weight().get(DispatchClass::Mandatory),
+            Weight::zero()
+        );
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        let registered = *System::block_weight().get(DispatchClass::Mandatory);
+        assert!(registered.ref_time() > 0);
+        assert!(registered.all_lte(NodeRewards::max_contract_gas_limit()));
+    });
+}
+
+#[test]
+fn admin_retries_ignore_the_backoff() {
+    new_test_ext().execute_with(|| {
+        UseContract::set(true);
+        NodeRewards::update_rewards(1, vec![(NODE, 400, UPPER)]);
+        assert_ok!(NodeRewards::retry_reward_update(RawOrigin::Root.into(), 1));
+        System::assert_last_event(Event::RewardUpdateRetryFailed(1, 1, 11).into());
+        UseContract::set(false);
+        assert_ok!(NodeRewards::retry_reward_update(RawOrigin::Root.into(), 1));
+        System::assert_last_event(Event::RewardUpdateRetrySucceeded(1, 2).into());
+        assert_noop!(
+            NodeRewards::retry_reward_update(RawOrigin::Root.into(), 1),
+            Error::<Test>::UnknownRewardUpdate
+        );
+    });
+}
+
+#[test]
+fn failed_sessions_with_too_many_nodes_are_dropped_whole() {
+    new_test_ext().execute_with(|| {
+        UseContract::set(true);
+        let nodes = (1..=5).map(|node| (node, 100, UPPER)).collect();
+        NodeRewards::update_rewards(1, nodes);
+        System::assert_last_event(Event::RewardUpdateDropped(1).into());
+        assert!(NodeRewards::failed_reward_update(1).is_none());
+        assert!(NodeRewards::reward_retry_queue().is_empty());
+    });
+}
+
+#[test]
+fn contract_gas_limit_is_kept_well_below_the_block() {
+    new_test_ext().execute_with(|| {
+        let ceiling = NodeRewards::max_contract_gas_limit();
+        assert_eq!(NodeRewards::contract_call_config().gas_limit, ceiling);
+        let mut config = NodeRewards::contract_call_config();
+        config.gas_limit = ceiling.saturating_add(Weight::from_parts(1, 0));
+        assert_noop!(
+            NodeRewards::set_contract_call_config(RawOrigin::Root.into(), config.clone()),
+            Error::<Test>::GasLimitExceedsBlockWeight
+        );
+        config.gas_limit = ceiling;
+        assert_ok!(NodeRewards::set_contract_call_config(
+            RawOrigin::Root.into(),
+            config.clone()
+        ));
+        System::assert_last_event(Event::ContractCallConfigUpdated(config).into());
+    });
+}
+
+#[test]
+fn reward_history_records_what_the_backend_issued() {
+    new_test_ext().execute_with(|| {
+        let nodes = vec![(NODE, 300, UPPER), (OTHER_NODE, 100, NodeTier::StandBy)];
+        NodeRewards::update_rewards(1, nodes.clone());
+        assert_eq!(
+            NodeRewards::reward_history(1),
+            Some(SessionRewardRecord {
+                nodes: BoundedVec::truncate_from(nodes),
+                total_votes: 400,
+                reward_pot: Some(1_000),
+                outcome: RewardOutcome::Issued,
+                truncated: false,
+            })
+        );
+        UseContract::set(true);
+        NodeRewards::update_rewards(2, vec![(NODE, 300, UPPER)]);
+        let record = NodeRewards::reward_history(2).unwrap();
+        assert_eq!(
+            (record.reward_pot, record.outcome),
+            (None, RewardOutcome::Failed)
+        );
+        UseContract::set(false);
+        assert_ok!(NodeRewards::retry_reward_update(RawOrigin::Root.into(), 2));
+        let record = NodeRewards::reward_history(2).unwrap();
+        assert_eq!(
+            (record.reward_pot, record.outcome),
+            (Some(1_000), RewardOutcome::IssuedOnRetry(1))
+        );
+    });
+}
+
+#[test]
+fn reward_history_flags_records_cut_to_max_rewarded_nodes() {
+    new_test_ext().execute_with(|| {
+        let nodes: Vec<_> = (1..=5).map(|node| (node, 100, UPPER)).collect();
+        NodeRewards::update_rewards(1, nodes.clone());
+        let record = NodeRewards::reward_history(1).unwrap();
+        assert_eq!(record.nodes.into_inner(), nodes[..4].to_vec());
+        assert_eq!(record.total_votes, 500);
+        assert!(record.truncated);
+        assert_eq!(record.outcome, RewardOutcome::Issued);
+    });
+}
+
+#[test]
+fn reward_history_keeps_the_last_sessions_only() {
+    new_test_ext().execute_with(|| {
+        for session in 1..=4 {
+            NodeRewards::update_rewards(session, vec![(NODE, 100, UPPER)]);
+        }
+        assert!(NodeRewards::reward_history(1).is_none());
+        assert!((2..=4).all(|session| NodeRewards::reward_history(session).is_some()));
+    });
+}
+
+#[test]
+fn runtime_api_queries_return_history_and_pending_rewards() {
+    new_test_ext().execute_with(|| {
+        setup_supported_node();
+        assert_eq!(NodeRewards::session_rewards(1), None);
+        for session in 1..=2 {
+            NodeRewards::update_rewards(session, vec![(NODE, 400, UPPER)]);
+        }
+        assert_eq!(NodeRewards::pending_rewards(NODE), 1_250);
+        assert_eq!(NodeRewards::pending_rewards(SUPPORTER), 750);
+        assert_ok!(NodeRewards::claim_rewards(RawOrigin::Signed(NODE).into()));
+        for session in 3..=4 {
+            NodeRewards::update_rewards(session, vec![(NODE, 400, UPPER)]);
+        }
+        // RewardHistoryDepth is 3, so session 1 has been pruned
+        assert_eq!(NodeRewards::session_rewards(1), None);
+        for session in 2..=4 {
+            assert_eq!(
+                NodeRewards::session_rewards(session),
+                Some(SessionRewardRecord {
+                    nodes: BoundedVec::truncate_from(vec![(NODE, 400, UPPER)]),
+                    total_votes: 400,
+                    reward_pot: Some(1_000),
+                    outcome: RewardOutcome::Issued,
+                    truncated: false,
+                })
+            );
+        }
+        assert_eq!(NodeRewards::session_rewards(5), None);
+        // only what was issued since the claim is pending for NODE
+        assert_eq!(NodeRewards::pending_rewards(NODE), 1_250);
+        assert_eq!(NodeRewards::pending_rewards(SUPPORTER), 1_500);
+        assert_eq!(NodeRewards::pending_rewards(OTHER_NODE), 0);
+    });
+}
+
+#[test]
+fn call_settings_migration_stores_the_capped_gas_limit() {
+    use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<NodeRewards>();
+        assert!(!ContractCallSettings::<Test>::exists());
+
+        migration::MigrateToContractCallSettings::<Test>::on_runtime_upgrade();
+        let config = NodeRewards::contract_call_config();
+        // the mock block allows exactly DEFAULT_CONTRACT_GAS_LIMIT, so a tenth of it is kept
+        assert_eq!(
+            config.gas_limit.ref_time(),
+            DEFAULT_CONTRACT_GAS_LIMIT / 10
+        );
+        assert_eq!(config.selector, [0x93, 0x44, 0x0f, 0x8d]);
+        assert!(ContractCallSettings::<Test>::exists());
+        assert_eq!(StorageVersion::get::<NodeRewards>(), 2);
+
+        // later changes survive running it again
+        let mut changed = config.clone();
+        changed.gas_limit = Weight::from_parts(1_000, 1_000);
+        assert_ok!(NodeRewards::set_contract_call_config(
+            RawOrigin::Root.into(),
+            changed.clone()
+        ));
+        migration::MigrateToContractCallSettings::<Test>::on_runtime_upgrade();
+        assert_eq!(NodeRewards::contract_call_config(), changed);
+    });
+}
+
+#[test]
+fn a_cancelled_admin_proposal_cannot_be_accepted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(NodeRewards::set_pallet_admin(RawOrigin::Root.into(), NODE));
+        assert_ok!(NodeRewards::set_pallet_admin(
+            RawOrigin::Signed(NODE).into(),
+            SUPPORTER
+        ));
+        assert_ok!(NodeRewards::cancel_pallet_admin(RawOrigin::Signed(NODE).into()));
+        System::assert_last_event(Event::AdminProposalCancelled(SUPPORTER).into());
+        System::set_block_number(10);
+        assert_noop!(
+            NodeRewards::accept_pallet_admin(RawOrigin::Signed(SUPPORTER).into()),
+            Error::<Test>::NoPendingAdmin
+        );
+        assert_eq!(NodeRewards::pallet_admin(), Some(NODE));
+    });
+}
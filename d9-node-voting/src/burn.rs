@@ -0,0 +1,77 @@
+use crate::pallet::Config;
+use crate::BalanceOf;
+use codec::Encode;
+use frame_support::{
+    inherent::Vec,
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement, Imbalance, WithdrawReasons},
+    weights::Weight,
+};
+
+pub type ContractBalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// burns tokens in exchange for voting interest
+pub trait BurnHandler<AccountId, Balance> {
+    /// burn `amount` taken from `burner`, on behalf of `beneficiary`
+    fn burn(burner: &AccountId, beneficiary: &AccountId, amount: Balance) -> DispatchResult;
+}
+
+/// burns directly from the burner's free balance, lowering total issuance
+pub struct NativeBurnHandler<T>(PhantomData<T>);
+impl<T: Config> BurnHandler<T::AccountId, BalanceOf<T>> for NativeBurnHandler<T> {
+    fn burn(burner: &T::AccountId, _: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        let withdrawn = T::Currency::withdraw(
+            burner,
+            amount,
+            WithdrawReasons::TRANSFER,
+            ExistenceRequirement::KeepAlive,
+        )?;
+        // `burn` lowers total issuance, so the two imbalances cancel out
+        let _ = T::Currency::burn(amount).offset(withdrawn);
+        Ok(())
+    }
+}
+
+/// burns by calling the burn contract through the main pool contract
+///
+/// `MainPool` is the contract called and `BurnContract` is passed on to it.
+pub struct ContractBurnHandler<T, MainPool, BurnContract>(PhantomData<(T, MainPool, BurnContract)>);
+impl<T, MainPool, BurnContract> BurnHandler<T::AccountId, BalanceOf<T>>
+    for ContractBurnHandler<T, MainPool, BurnContract>
+where
+    T: Config + pallet_contracts::Config,
+    ContractBalanceOf<T>: From<BalanceOf<T>>,
+    MainPool: Get<T::AccountId>,
+    BurnContract: Get<T::AccountId>,
+{
+    fn burn(
+        burner: &T::AccountId,
+        beneficiary: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        let weight = Weight::from_parts(50_000_000_000, 800_000);
+        //0xb1efc17b
+        let mut selector: Vec<u8> = [0xb1, 0xef, 0xc1, 0x7b].into();
+        let mut encoded_voter: Vec<u8> = beneficiary.encode();
+        let mut encoded_burn_contract: Vec<u8> = BurnContract::get().encode();
+        let mut data_for_contract_call = Vec::new();
+        data_for_contract_call.append(&mut selector);
+        data_for_contract_call.append(&mut encoded_voter);
+        data_for_contract_call.append(&mut encoded_burn_contract);
+
+        pallet_contracts::Pallet::<T>::bare_call(
+            burner.clone(),
+            MainPool::get(),
+            amount.into(),
+            weight,
+            None,
+            data_for_contract_call,
+            false,
+            pallet_contracts::Determinism::Enforced,
+        )
+        .result
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::pallet::{Config, NodeToUserVotesTotals, Pallet};
+use frame_support::{inherent::Vec, pallet_prelude::*};
+use sp_npos_elections::{seq_phragmen, VoteWeight};
+use sp_runtime::{PerThing, Perbill, Saturating};
+use sp_std::collections::btree_map::BTreeMap;
+
+/// picks the validators of a new session from the candidates
+pub trait ElectionStrategy<AccountId> {
+    /// choose at most `to_elect` validators, best first
+    fn elect(to_elect: u32) -> Vec<AccountId>;
+
+    /// worst case weight of `elect`
+    fn weight(to_elect: u32) -> Weight;
+}
+
+/// reads made by `Pallet::get_electable_candidates`
+fn electable_candidates_weight<T: Config>() -> Weight {
+    let reads = u64::from(T::MaxCandidates::get()).saturating_mul(2).saturating_add(1);
+    T::DbWeight::get()
+        .reads(reads)
+        .saturating_add(Pallet::<T>::sorted_candidates_weight())
+}
+
+fn supporter_edges<T: Config>() -> u64 {
+    u64::from(T::MaxCandidates::get()).saturating_mul(T::MaxSupportersPerCandidate::get().into())
+}
+
+/// the electable candidates with the most votes
+pub struct TopVotes<T>(PhantomData<T>);
+impl<T: Config> ElectionStrategy<T::AccountId> for TopVotes<T> {
+    fn elect(to_elect: u32) -> Vec<T::AccountId> {
+        let mut candidates = Pallet::<T>::get_electable_candidates();
+        candidates.truncate(to_elect as usize);
+        candidates
+    }
+
+    fn weight(_: u32) -> Weight {
+        electable_candidates_weight::<T>()
+    }
+}
+
+/// sequential phragmén over the voters' delegations
+///
+/// every voter backs each electable candidate it delegated to with all of its votes delegated to
+/// electable candidates, less the decay it owes whether settled or not, so a voter spreading votes over many candidates gains no
+/// more seats than its share of all votes. only the `MaxVoters` voters with the most votes take
+/// part. seats phragmén leaves empty, e.g. when too few candidates have support, are filled by
+/// vote count.
+///
+/// supporters are read per candidate, so the snapshot is bounded by `MaxCandidates` times
+/// `MaxSupportersPerCandidate`.
+pub struct SequentialPhragmen<T, MaxVoters>(PhantomData<(T, MaxVoters)>);
+impl<T: Config, MaxVoters: Get<u32>> ElectionStrategy<T::AccountId>
+    for SequentialPhragmen<T, MaxVoters>
+{
+    fn elect(to_elect: u32) -> Vec<T::AccountId> {
+        let candidates = Pallet::<T>::get_electable_candidates();
+        let mut voters: BTreeMap<T::AccountId, (VoteWeight, Vec<T::AccountId>)> = BTreeMap::new();
+        for candidate in candidates.iter() {
+            for (voter, votes) in NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),)) {
+                let retained =
+                    Perbill::one().saturating_sub(Pallet::<T>::pending_vote_decay(&voter));
+                let votes = retained.mul_floor(votes);
+                let (stake, targets) = voters.entry(voter).or_insert_with(|| (0, Vec::new()));
+                *stake = stake.saturating_add(votes);
+                targets.push(candidate.clone());
+            }
+        }
+        let mut voters = voters
+            .into_iter()
+            .map(|(voter, (stake, targets))| (voter, stake, targets))
+            .collect::<Vec<_>>();
+        voters.sort_by(|a, b| b.1.cmp(&a.1));
+        voters.truncate(MaxVoters::get() as usize);
+
+        let mut elected = seq_phragmen::<T::AccountId, Perbill>(
+            to_elect as usize,
+            candidates.clone(),
+            voters,
+            None,
+        )
+        .map(|result| result.winners.into_iter().map(|(winner, _)| winner).collect())
+        .unwrap_or_else(|_| Vec::new());
+        for candidate in candidates {
+            if elected.len() >= to_elect as usize {
+                break;
+            }
+            if !elected.contains(&candidate) {
+                elected.push(candidate);
+            }
+        }
+        elected
+    }
+
+    /// reads of every supporter entry and its decay, phragmén itself is not benchmarked
+    fn weight(_: u32) -> Weight {
+        electable_candidates_weight::<T>()
+            .saturating_add(T::DbWeight::get().reads(supporter_edges::<T>().saturating_mul(2)))
+    }
+}
@@ -1,48 +1,133 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use sp_staking::SessionIndex;
 use sp_std::prelude::*;
+mod burn;
+mod election;
+pub mod migration;
+mod performance;
+mod runtime_api;
+#[cfg(test)]
+mod tests;
 mod types;
-use frame_support::traits::Currency;
+use frame_support::traits::{Currency, ReservableCurrency};
 pub use pallet::*;
-use sp_arithmetic::Perquintill;
+use sp_arithmetic::{PerThing, Perquintill};
+pub use burn::*;
+pub use election::*;
+pub use performance::*;
+pub use runtime_api::*;
 pub use types::*;
 
-pub type BalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<
     <T as frame_system::Config>::AccountId,
 >>::Balance;
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+pub type PalletAdminHandover<T> = d9_admin::AdminHandover<
+    <T as frame_system::Config>::AccountId,
+    <T as frame_system::Config>::BlockNumber,
+    PalletAdmin<T>,
+    PendingPalletAdmin<T>,
+>;
+/// root or the pallet admin
+pub type EnsurePalletAdmin<T> =
+    d9_admin::EnsureRootOrAdmin<<T as frame_system::Config>::AccountId, PalletAdmin<T>>;
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::{
         inherent::Vec,
         pallet_prelude::{DispatchResult, OptionQuery, ValueQuery, *},
+        traits::{
+            LockIdentifier, LockableCurrency, OnKilledAccount, OnUnbalanced, WithdrawReasons,
+        },
         weights::Weight,
         Blake2_128Concat, BoundedVec,
     };
     //  use sp_std::vec;
-    use frame_system::{
-        ensure_root,
-        pallet_prelude::{OriginFor, *},
-    };
+    use frame_system::pallet_prelude::{OriginFor, *};
 
+    use d9_admin::{AdminChange, AdminError, AdminErrors, PendingAdmin};
     use pallet_session::SessionManager;
-    use sp_runtime::Saturating;
+    use sp_runtime::{traits::Zero, Perbill, Saturating};
 
     const STORAGE_VERSION: frame_support::traits::StorageVersion =
-        frame_support::traits::StorageVersion::new(1);
+        frame_support::traits::StorageVersion::new(5);
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
-    pub trait Config: frame_system::Config + pallet_contracts::Config {
+    pub trait Config: frame_system::Config {
         type CurrencySubUnits: Get<BalanceOf<Self>>;
-        type Currency: Currency<Self::AccountId>;
+        type Currency: ReservableCurrency<Self::AccountId>;
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type MaxCandidates: Get<u32>;
         type MaxValidatorNodes: Get<u32>;
         type NodeRewardManager: NodeRewardManager<Self::AccountId>;
         type ReferendumManager: ReferendumManager;
+        /// burns the tokens paid for voting interest
+        type BurnHandler: BurnHandler<Self::AccountId, BalanceOf<Self>>;
+        /// whether a reaped account loses its voting interest and delegations (`true`) or keeps
+        /// them (`false`). voting interest is bought by burning tokens, so pruning forfeits it.
+        type PruneReapedAccounts: Get<bool>;
+        /// currency in which tokens are locked for voting interest
+        type LockableCurrency: LockableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+        /// lock id under which voting tokens are locked
+        type VotingLockIdentifier: Get<LockIdentifier>;
+        /// whether voting interest is bought by burning or by locking tokens
+        type VotingInterestMode: Get<VotingInterestMode>;
+        /// sessions unlocked voting tokens stay locked before they can be withdrawn
+        type UnbondingPeriod: Get<SessionIndex>;
+        /// maximum number of unlocking chunks a voter can have at once
+        type MaxUnlockingChunks: Get<u32>;
+        /// amount reserved from a candidate for as long as it stands
+        type CandidacyBond: Get<BalanceOf<Self>>;
+        /// sessions a removed candidate's bond stays reserved, and slashable, before it can be
+        /// withdrawn
+        type CandidacyBondUnbondingPeriod: Get<SessionIndex>;
+        /// maximum number of accounts that can delegate to one candidate
+        type MaxSupportersPerCandidate: Get<u32>;
+        /// maximum number of delegations returned to voters per block from removed candidates,
+        /// and of voters whose vote decay `on_idle` settles per block
+        type MaxCleanupPerBlock: Get<u32>;
+        /// maximum number of candidates evicted at the start of a session
+        type EvictionCount: Get<u32>;
+        /// sessions after registering during which a candidate cannot be evicted
+        type CandidateGracePeriod: Get<SessionIndex>;
+        /// candidates with fewer votes may be evicted even when the list is not full
+        type MinimumCandidateVotes: Get<u64>;
+        /// number of past sessions for which `SessionNodeList` is kept. must be at least one
+        type SessionHistoryDepth: Get<SessionIndex>;
+        /// share of a voter's delegated weight lost each session since they last reaffirmed.
+        /// zero disables decay. `on_idle` brings every voter's decay into the candidate totals
+        /// once per session as blocks allow. rankings and elections count the decay owed by
+        /// then without waiting for it
+        type VoteDecayRate: Get<Perbill>;
+        /// chooses the validators of each session among the candidates
+        type ElectionStrategy: ElectionStrategy<Self::AccountId>;
+        /// reports how validators performed in each session
+        type ValidatorPerformanceProvider: ValidatorPerformanceProvider<Self::AccountId>;
+        /// decides when an underperforming validator is demoted to standby
+        type DemotionRule: DemotionRule;
+        /// sessions a demoted validator is left out of elections
+        type StandbyPeriod: Get<SessionIndex>;
+        /// sessions between announcing a `sharing_percent` change and it taking effect
+        type SharingPercentNoticePeriod: Get<SessionIndex>;
+        /// number of candidates below the validators ranked `NodeTier::StandBy`
+        type MaxStandbyNodes: Get<u32>;
+        /// votes a candidate must delegate to itself to be elected
+        type MinimumSelfStake: Get<u64>;
+        /// whether voters may transfer undelegated voting interest themselves. governance can
+        /// always force a transfer.
+        type AllowVotingInterestTransfers: Get<bool>;
+        /// origin allowed to slash candidacy bonds and force voting interest transfers
+        type GovernanceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// receives slashed candidacy bonds
+        type OnCandidacyBondSlashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+        /// timelock on handing over the pallet admin role, see `d9_admin`
+        type AdminTransferDelay: Get<Self::BlockNumber>;
     }
 
     /// defines the voting power of a user
@@ -56,7 +141,6 @@ pub mod pallet {
     /// UserToNodeVotesTotals == NodeToUserVotesTotals
     #[pallet::storage]
     #[pallet::getter(fn user_to_node_votes)]
-    #[pallet::unbounded]
     pub type UserToNodeVotesTotals<T: Config> = StorageNMap<
         Key = (
             NMapKey<Blake2_128Concat, T::AccountId>,
@@ -72,7 +156,6 @@ pub mod pallet {
     /// UserToNodeVotesTotals == NodeToUserVotesTotals
     #[pallet::storage]
     #[pallet::getter(fn node_to_user_votes)]
-    #[pallet::unbounded]
     pub type NodeToUserVotesTotals<T: Config> = StorageNMap<
         Key = (
             NMapKey<Blake2_128Concat, T::AccountId>,
@@ -89,17 +172,55 @@ pub mod pallet {
     #[pallet::getter(fn node_votes)]
     pub type NodeAccumulativeVotes<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, u64, OptionQuery>;
+    /// candidates with their votes, most votes first
+    ///
+    /// kept in step with `NodeAccumulativeVotes` so sessions need not sort every candidate
+    #[pallet::storage]
+    #[pallet::getter(fn sorted_candidates)]
+    pub type SortedCandidates<T: Config> =
+        StorageValue<_, BoundedVec<(T::AccountId, u64), T::MaxCandidates>, ValueQuery>;
+
+    /// session in which each candidate registered
+    #[pallet::storage]
+    #[pallet::getter(fn candidate_registered_at)]
+    pub type CandidateRegisteredAt<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SessionIndex, OptionQuery>;
+
+    /// number of accounts delegating to each candidate
+    #[pallet::storage]
+    #[pallet::getter(fn candidate_supporter_count)]
+    pub type CandidateSupporterCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// removed candidates whose delegations are still being returned to voters in `on_idle`
+    #[pallet::storage]
+    #[pallet::getter(fn candidate_cleanup_queue)]
+    pub type CandidateCleanupQueue<T: Config> =
+        StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn total_number_of_candidate_nodes)]
     pub type CurrentNumberOfCandidatesNodes<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+    /// sorted candidates of each session, kept for `SessionHistoryDepth` sessions
     #[pallet::storage]
     #[pallet::getter(fn session_node_list)]
     pub type SessionNodeList<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
         SessionIndex,
-        BoundedVec<T::AccountId, ConstU32<300>>,
+        BoundedVec<T::AccountId, T::MaxCandidates>,
+        OptionQuery,
+    >;
+
+    /// validators chosen by `ElectionStrategy` for an upcoming session
+    #[pallet::storage]
+    #[pallet::getter(fn elected_validators)]
+    pub type ElectedValidators<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        SessionIndex,
+        BoundedVec<T::AccountId, T::MaxValidatorNodes>,
         OptionQuery,
     >;
 
@@ -112,21 +233,175 @@ pub mod pallet {
     pub type CurrentValidatorVoteStats<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, ValidatorVoteStats<T>, OptionQuery>;
 
+    /// performance of each validator over the sessions it validated
+    #[pallet::storage]
+    #[pallet::getter(fn validator_performance)]
+    pub type ValidatorPerformance<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ValidatorPerformanceStats, ValueQuery>;
+
+    /// validators on standby, with the last session they are left out of elections
+    #[pallet::storage]
+    #[pallet::getter(fn demoted_until)]
+    pub type DemotedValidators<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SessionIndex, OptionQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn node_metadata)]
-    pub type NodeMetadata<T: Config> =
-        StorageMap<_, Blake2_128Concat, T::AccountId, NodeMetadataStruct, OptionQuery>;
+    pub type NodeMetadata<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        NodeMetadataStruct<T::AccountId>,
+        OptionQuery,
+    >;
+
+    /// tier of every candidate in the last ended session, in ranking order
+    #[pallet::storage]
+    #[pallet::getter(fn node_tiers)]
+    pub type NodeTiers<T: Config> =
+        StorageValue<_, BoundedVec<(T::AccountId, NodeTier), T::MaxCandidates>, ValueQuery>;
+
+    /// announced `sharing_percent` changes waiting out `SharingPercentNoticePeriod`
+    #[pallet::storage]
+    #[pallet::getter(fn pending_sharing_percent)]
+    pub type PendingSharingPercent<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SharingPercentChange, OptionQuery>;
+
+    /// bond reserved from each candidate
+    #[pallet::storage]
+    #[pallet::getter(fn candidacy_bond)]
+    pub type CandidacyBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// bonds of removed candidates, still reserved until the unbonding period has passed
+    #[pallet::storage]
+    #[pallet::getter(fn unbonding_candidacy_bond)]
+    pub type UnbondingCandidacyBonds<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, UnlockChunk<BalanceOf<T>>, OptionQuery>;
+
+    /// tokens each account has burned for voting interest
+    #[pallet::storage]
+    #[pallet::getter(fn burned_for_voting_interest)]
+    pub type BurnedForVotingInterest<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// tokens burned for voting interest across all accounts
+    #[pallet::storage]
+    #[pallet::getter(fn total_burned_for_voting_interest)]
+    pub type TotalBurnedForVotingInterest<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// tokens a voter has locked that currently back voting interest
+    #[pallet::storage]
+    #[pallet::getter(fn voting_lock)]
+    pub type VotingLocks<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
+    /// tokens a voter has unlocked that are still waiting out the unbonding period
+    #[pallet::storage]
+    #[pallet::getter(fn voting_unlocking)]
+    pub type VotingUnlocking<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<UnlockChunk<BalanceOf<T>>, T::MaxUnlockingChunks>,
+        ValueQuery,
+    >;
+
+    /// session in which each voter last reaffirmed their delegations
+    #[pallet::storage]
+    #[pallet::getter(fn voter_last_affirmed)]
+    pub type VoterLastAffirmed<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, SessionIndex, OptionQuery>;
+
+    /// share of each voter's delegated votes left out of candidate totals because of decay
+    #[pallet::storage]
+    #[pallet::getter(fn applied_vote_decay)]
+    pub type AppliedVoteDecay<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Perbill, ValueQuery>;
+
+    /// session and last voter of an unfinished `on_idle` sweep settling vote decay
+    #[pallet::storage]
+    #[pallet::getter(fn vote_decay_cursor)]
+    pub type VoteDecayCursor<T: Config> =
+        StorageValue<_, (SessionIndex, T::AccountId), OptionQuery>;
+
+    /// latest session for which every voter's decay was settled by `on_idle`
+    #[pallet::storage]
+    #[pallet::getter(fn vote_decay_swept_session)]
+    pub type VoteDecaySweptSession<T: Config> = StorageValue<_, SessionIndex, OptionQuery>;
+
+    /// account each voter allows to manage their delegations
+    #[pallet::storage]
+    #[pallet::getter(fn vote_manager)]
+    pub type VoteManagers<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn pallet_admin)]
     pub type PalletAdmin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    /// successor proposed by the pallet admin, waiting to accept
+    #[pallet::storage]
+    #[pallet::getter(fn pending_pallet_admin)]
+    pub type PendingPalletAdmin<T: Config> =
+        StorageValue<_, PendingAdmin<T::AccountId, T::BlockNumber>, OptionQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         CandidacySubmitted(T::AccountId),
         VotesDelegatedBy(T::AccountId),
         CandidacyRemoved(T::AccountId),
+        /// all delegations to a removed candidate were returned to their voters
+        CandidateCleanupCompleted(T::AccountId),
+        /// (candidate, votes) evicted at the start of a session
+        CandidateEvicted(T::AccountId, u64),
+        /// voting interest and delegations of a reaped account were removed
+        VotingInterestPruned(T::AccountId),
+        /// (candidate, bond reserved)
+        CandidacyBondReserved(T::AccountId, BalanceOf<T>),
+        /// (candidate, bond unbonding, session from which it can be withdrawn)
+        CandidacyBondUnbonding(T::AccountId, BalanceOf<T>, SessionIndex),
+        /// (candidate, bond returned)
+        CandidacyBondReturned(T::AccountId, BalanceOf<T>),
+        /// (candidate, bond slashed)
+        CandidacyBondSlashed(T::AccountId, BalanceOf<T>),
+        /// (burner, beneficiary, amount burned, votes gained)
+        VotingInterestBought(T::AccountId, T::AccountId, BalanceOf<T>, u64),
+        /// (voter, amount locked, votes gained)
+        VotingInterestLocked(T::AccountId, BalanceOf<T>, u64),
+        /// (voter, amount unlocked, votes removed, session from which it can be withdrawn)
+        VotingInterestUnbonding(T::AccountId, BalanceOf<T>, u64, SessionIndex),
+        /// (voter, amount released from the voting lock)
+        UnlockedVotingTokensWithdrawn(T::AccountId, BalanceOf<T>),
+        /// delegations of a voter were restored to full weight
+        VotesReaffirmed(T::AccountId),
+        /// (voter, share of delegated votes now decayed)
+        VoteDecaySettled(T::AccountId, Perbill),
+        /// (validator, last session it is on standby)
+        ValidatorDemoted(T::AccountId, SessionIndex),
+        /// (candidate, votes it delegates to itself)
+        SelfStakeDeclared(T::AccountId, u64),
+        /// (candidate, new sharing percent, session it applies from)
+        SharingPercentChangeAnnounced(T::AccountId, u8, SessionIndex),
+        /// (candidate, sharing percent now in effect)
+        SharingPercentChanged(T::AccountId, u8),
+        /// a candidate's pending sharing percent change was withdrawn
+        SharingPercentChangeCancelled(T::AccountId),
+        /// (voter, (candidate, votes before, votes after) of every changed delegation)
+        DelegationsRebalanced(T::AccountId, Vec<(T::AccountId, u64, u64)>),
+        /// (voter, manager)
+        VoteManagerSet(T::AccountId, T::AccountId),
+        /// (voter)
+        VoteManagerRemoved(T::AccountId),
+        /// (from, to, votes)
+        VotingInterestTransferred(T::AccountId, T::AccountId, u64),
+        /// (proposed admin, first block it may accept)
+        AdminProposed(T::AccountId, T::BlockNumber),
+        /// (old admin, new admin)
+        AdminChanged(Option<T::AccountId>, T::AccountId),
+        /// (proposed admin) whose proposal was withdrawn
+        AdminProposalCancelled(T::AccountId),
     }
 
     #[pallet::error]
@@ -146,7 +421,48 @@ pub mod pallet {
         AtMaximumNumberOfCandidates,
         BurnAmountMustBeGreaterThan100,
         SupporterShareOutOfRange,
-        CurrentValidatorCanNotChangeSharePercentage,
+        WrongVotingInterestMode,
+        LockAmountTooSmall,
+        InsufficientFreeBalance,
+        UnlockingMoreThanLocked,
+        TooManyUnlockingChunks,
+        NothingToWithdraw,
+        InsufficientBalanceForCandidacyBond,
+        CandidacyBondStillUnbonding,
+        CandidateHasMaxSupporters,
+        CandidateCleanupPending,
+        DuplicateCandidateInDelegations,
+        NotVoteManager,
+        VotingInterestTransfersDisabled,
+        CannotTransferToSelf,
+        NoPendingAdmin,
+        NotPendingAdmin,
+        AdminTimelockActive,
+    }
+
+    impl<T: Config> AdminErrors for Error<T> {
+        const NO_PENDING_ADMIN: Self = Self::NoPendingAdmin;
+        const NOT_PENDING_ADMIN: Self = Self::NotPendingAdmin;
+        const TIMELOCK_ACTIVE: Self = Self::AdminTimelockActive;
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let used = Self::cleanup_removed_candidates(remaining_weight);
+            used.saturating_add(Self::settle_vote_decay_batch(remaining_weight.saturating_sub(used)))
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::MaxCleanupPerBlock::get() > 0,
+                "removed candidates are never cleaned up with a MaxCleanupPerBlock of zero"
+            );
+            assert!(
+                T::SessionHistoryDepth::get() > 0,
+                "a SessionHistoryDepth of zero prunes each session's node list as it is written"
+            );
+        }
     }
 
     #[pallet::genesis_config]
@@ -158,7 +474,7 @@ pub mod pallet {
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
             for candidate in self.initial_candidates.iter() {
-                NodeAccumulativeVotes::<T>::insert(candidate.clone(), 1000);
+                Pallet::<T>::set_candidate_votes(candidate, 1000);
                 CurrentNumberOfCandidatesNodes::<T>::put(
                     CurrentNumberOfCandidatesNodes::<T>::get() + 1,
                 );
@@ -181,103 +497,113 @@ pub mod pallet {
         #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
         pub fn submit_candidacy(
             origin: OriginFor<T>,
-            candidate_metadata: NodeMetadataStruct,
+            candidate_metadata: NodeMetadataStruct<T::AccountId>,
         ) -> DispatchResult {
             let candidate_node = ensure_signed(origin)?;
             let candidate_votes_opt = NodeAccumulativeVotes::<T>::get(candidate_node.clone());
             if candidate_votes_opt.is_some() {
                 return Err(Error::<T>::CandidateAlreadyExists.into());
             }
+            if CandidateCleanupQueue::<T>::get().contains(&candidate_node) {
+                return Err(Error::<T>::CandidateCleanupPending.into());
+            }
             let current_candidate_count = CurrentNumberOfCandidatesNodes::<T>::get();
             let max_candidates = T::MaxCandidates::get();
             if current_candidate_count + 1 > max_candidates {
                 return Err(Error::<T>::AtMaximumNumberOfCandidates.into());
             }
 
-            NodeAccumulativeVotes::<T>::insert(candidate_node.clone(), 0);
+            let bond = T::CandidacyBond::get();
+            T::Currency::reserve(&candidate_node, bond)
+                .map_err(|_| Error::<T>::InsufficientBalanceForCandidacyBond)?;
+            CandidacyBonds::<T>::insert(&candidate_node, bond);
+            Self::deposit_event(Event::CandidacyBondReserved(candidate_node.clone(), bond));
+
+            Self::set_candidate_votes(&candidate_node, 0);
             let current_index = CurrentSessionIndex::<T>::get();
             CurrentNumberOfCandidatesNodes::<T>::put(current_candidate_count + 1);
-            let node_metadata: NodeMetadataStruct = NodeMetadataStruct {
-                name: candidate_metadata.name.clone(),
-                sharing_percent: candidate_metadata.sharing_percent.clone(),
+            let node_metadata = NodeMetadataStruct {
                 index_of_last_percent_change: current_index,
+                ..candidate_metadata
             };
             NodeMetadata::<T>::insert(candidate_node.clone(), node_metadata);
+            CandidateRegisteredAt::<T>::insert(&candidate_node, current_index);
             Self::deposit_event(Event::CandidacySubmitted(candidate_node));
             Ok(())
         }
 
+        /// burn `amount_to_burn` of the caller's tokens through `BurnHandler` to give
+        /// `beneficiary_voter` voting interest
+        ///
+        /// only available when `VotingInterestMode` is `Burn`
         #[pallet::call_index(1)]
         #[pallet::weight(T::DbWeight::get().reads_writes(5, 5))]
         pub fn add_voting_interest(
             origin: OriginFor<T>,
             beneficiary_voter: T::AccountId,
-            main_pool: T::AccountId,
             amount_to_burn: BalanceOf<T>,
-            burn_contract: T::AccountId,
         ) -> DispatchResult {
             let token_burner = ensure_signed(origin)?;
-            let weight = Weight::from_parts(50_000_000_000, 800_000);
-            Self::call_burn_contract(
-                token_burner,
-                beneficiary_voter.clone(),
-                main_pool,
-                amount_to_burn,
-                burn_contract,
-                weight,
-            )?;
+            ensure!(
+                T::VotingInterestMode::get() == VotingInterestMode::Burn,
+                Error::<T>::WrongVotingInterestMode
+            );
+            let decimals: BalanceOf<T> = T::CurrencySubUnits::get();
+            let burn_minimum: BalanceOf<T> = <BalanceOf<T>>::from(100u32).saturating_mul(decimals);
+            if amount_to_burn < burn_minimum {
+                return Err(Error::<T>::BurnAmountMustBeGreaterThan100.into());
+            }
+            T::BurnHandler::burn(&token_burner, &beneficiary_voter, amount_to_burn)?;
 
+            BurnedForVotingInterest::<T>::mutate(&token_burner, |burned| {
+                *burned = burned.saturating_add(amount_to_burn)
+            });
+            TotalBurnedForVotingInterest::<T>::mutate(|burned| {
+                *burned = burned.saturating_add(amount_to_burn)
+            });
             let voting_interest_increase = Self::calculate_voting_interests(amount_to_burn);
-            Self::add_voting_interest_internal(beneficiary_voter, voting_interest_increase);
+            Self::add_voting_interest_internal(beneficiary_voter.clone(), voting_interest_increase);
+            Self::deposit_event(Event::VotingInterestBought(
+                token_burner,
+                beneficiary_voter,
+                amount_to_burn,
+                voting_interest_increase,
+            ));
             Ok(())
         }
 
         #[pallet::call_index(2)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
         pub fn delegate_votes(
             origin: OriginFor<T>,
             delegations: Vec<ValidatorDelegations<T>>,
         ) -> DispatchResult {
             let delegator = ensure_signed(origin)?;
-            if delegations.len() == 0 {
-                return Err(Error::<T>::EmptyDelegationList.into());
-            }
-            if (delegations.len() as u32) > T::MaxCandidates::get() {
-                return Err(Error::<T>::DelegationListTooLarge.into());
-            }
-
-            let maybe_voting_interest = UsersVotingInterests::<T>::get(delegator.clone());
-            if maybe_voting_interest.is_none() {
-                return Err(Error::<T>::DelegatorHasNoVotingCapacity.into());
-            }
-            let voting_interest = maybe_voting_interest.unwrap();
-            Self::validate_delegations(&voting_interest, &delegations)?;
-            let _ = Self::delegate_votes_to_candidates(&delegator, delegations);
-            Self::deposit_event(Event::VotesDelegatedBy(delegator));
-            Ok(())
+            Self::do_delegate_votes(delegator, delegations)
         }
 
+        /// give up candidacy. delegations are returned to voters over the following blocks.
+        ///
+        /// the bond stays reserved for `CandidacyBondUnbondingPeriod` sessions.
         #[pallet::call_index(3)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(6, 7))]
         pub fn remove_candidacy(origin: OriginFor<T>) -> DispatchResult {
             let candidate: T::AccountId = ensure_signed(origin)?;
-            let mut support_to_remove: Vec<(T::AccountId, u64)> = Vec::new();
-            let mut prefix_iterator = NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),));
-            while let Some((supporter, delegated_votes)) = prefix_iterator.next() {
-                support_to_remove.push((supporter, delegated_votes));
-            }
-            for support in support_to_remove {
-                Self::remove_votes_from_candidate(&support.0, &candidate, support.1);
+            if !Self::is_valid_candidate(&candidate) {
+                return Err(Error::<T>::CandidateDoesNotExist.into());
             }
-            let current_candidate_count = CurrentNumberOfCandidatesNodes::<T>::get();
-            CurrentNumberOfCandidatesNodes::<T>::put(current_candidate_count - 1);
-            NodeMetadata::<T>::remove(candidate.clone());
+            Self::do_remove_candidate(&candidate);
+            Self::unbond_candidacy_bond(&candidate);
             Self::deposit_event(Event::CandidacyRemoved(candidate));
             Ok(())
         }
 
         #[pallet::call_index(4)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
         pub fn try_remove_votes_from_candidate(
             origin: OriginFor<T>,
             candidate: T::AccountId,
@@ -296,11 +622,14 @@ pub mod pallet {
                 return Err(Error::<T>::AttemptingToRemoveMoreVotesThanDelegated.into());
             }
 
+            Self::reaffirm(&voter);
             Self::remove_votes_from_candidate(&voter, &candidate, votes);
             Ok(())
         }
         #[pallet::call_index(5)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
         pub fn redistribute_votes(
             origin: OriginFor<T>,
             from: T::AccountId,
@@ -314,6 +643,8 @@ pub mod pallet {
             if delegated_votes == 0 {
                 return Err(Error::<T>::VoterDidntDelegateToThisCandidate.into());
             }
+            Self::ensure_can_support(&voter, &to)?;
+            Self::reaffirm(&voter);
             Self::remove_votes_from_candidate(&voter, &from, delegated_votes);
             Self::add_votes_to_candidate(&voter, &to, delegated_votes);
             Ok(())
@@ -339,8 +670,12 @@ pub mod pallet {
             Ok(())
         }
 
+        /// announce a new `sharing_percent`, applied at the start of the first session after
+        /// `SharingPercentNoticePeriod`
+        ///
+        /// a new announcement replaces a pending one. announcing the current percent cancels it.
         #[pallet::call_index(7)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 1))]
         pub fn change_candidate_supporter_share(
             origin: OriginFor<T>,
             sharing_percent: u8,
@@ -353,39 +688,456 @@ pub mod pallet {
             if !Self::is_valid_candidate(&node_id) {
                 return Err(Error::<T>::CandidateDoesNotExist.into());
             }
-
-            if CurrentValidatorVoteStats::<T>::contains_key(node_id.clone()) {
-                return Err(Error::<T>::CurrentValidatorCanNotChangeSharePercentage.into());
-            }
             let node_metadata_option = NodeMetadata::<T>::get(node_id.clone());
             if node_metadata_option.is_none() {
                 return Err(Error::<T>::ErrorGettingNodeMetadata.into());
             }
-            let mut node_metadata = node_metadata_option.unwrap();
-            let current_index = CurrentSessionIndex::<T>::get();
-            if node_metadata.index_of_last_percent_change > current_index.saturating_sub(3) {
-                return Err(Error::<T>::CurrentValidatorCanNotChangeSharePercentage.into());
+            let node_metadata = node_metadata_option.unwrap();
+            if sharing_percent == node_metadata.sharing_percent {
+                if PendingSharingPercent::<T>::take(&node_id).is_some() {
+                    Self::deposit_event(Event::SharingPercentChangeCancelled(node_id));
+                }
+                return Ok(());
             }
-            node_metadata.sharing_percent = sharing_percent.clone();
-            node_metadata.index_of_last_percent_change = current_index;
-            NodeMetadata::<T>::insert(node_id.clone(), node_metadata);
+            let effective_session = CurrentSessionIndex::<T>::get()
+                .saturating_add(T::SharingPercentNoticePeriod::get());
+            PendingSharingPercent::<T>::insert(
+                &node_id,
+                SharingPercentChange {
+                    sharing_percent,
+                    effective_session,
+                },
+            );
+            Self::deposit_event(Event::SharingPercentChangeAnnounced(
+                node_id,
+                sharing_percent,
+                effective_session,
+            ));
             Ok(())
         }
 
+        /// hand the pallet admin role to `new_admin`
+        ///
+        /// root sets the admin at once. the admin only proposes `new_admin`, who takes over with
+        /// `accept_pallet_admin` once `AdminTransferDelay` blocks have passed.
         #[pallet::call_index(8)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
         pub fn set_pallet_admin(origin: OriginFor<T>, new_admin: T::AccountId) -> DispatchResult {
-            let caller = ensure_signed(origin.clone())?;
-            let current_admin = PalletAdmin::<T>::get();
-            if current_admin.is_some() {
-                ensure!(
-                    current_admin.unwrap() == caller,
-                    "Only the current admin can set a new admin"
-                );
+            let caller = ensure_signed_or_root(origin)?;
+            let change = PalletAdminHandover::<T>::propose(
+                caller,
+                new_admin,
+                frame_system::Pallet::<T>::block_number(),
+                T::AdminTransferDelay::get(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            match change {
+                AdminChange::Proposed(proposed, acceptable_at) => {
+                    Self::deposit_event(Event::AdminProposed(proposed, acceptable_at))
+                }
+                AdminChange::Changed(old_admin, new_admin) => {
+                    Self::deposit_event(Event::AdminChanged(old_admin, new_admin))
+                }
+            }
+            Ok(())
+        }
+
+        /// lock `amount` of the caller's tokens to gain voting interest
+        ///
+        /// only available when `VotingInterestMode` is `Lock`
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 3).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn lock_voting_interest(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            ensure!(
+                T::VotingInterestMode::get() == VotingInterestMode::Lock,
+                Error::<T>::WrongVotingInterestMode
+            );
+            let locked = VotingLocks::<T>::get(&voter).unwrap_or_else(Zero::zero);
+            let new_locked = locked.saturating_add(amount);
+            let votes = Self::calculate_voting_interests(new_locked)
+                .saturating_sub(Self::calculate_voting_interests(locked));
+            ensure!(votes > 0, Error::<T>::LockAmountTooSmall);
+            let total_lock = Self::total_voting_lock(&voter).saturating_add(amount);
+            ensure!(
+                T::LockableCurrency::free_balance(&voter) >= total_lock,
+                Error::<T>::InsufficientFreeBalance
+            );
+
+            VotingLocks::<T>::insert(&voter, new_locked);
+            Self::update_voting_lock(&voter);
+            Self::reaffirm(&voter);
+            Self::add_voting_interest_internal(voter.clone(), votes);
+            Self::deposit_event(Event::VotingInterestLocked(voter, amount, votes));
+            Ok(())
+        }
+
+        /// start unbonding `amount` of the caller's locked voting tokens
+        ///
+        /// the voting interest they backed is removed at once. if that leaves more votes
+        /// delegated than the voter holds, the excess is taken from every candidate in
+        /// proportion to the votes delegated to it. the tokens stay locked for
+        /// `UnbondingPeriod` sessions.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 3).saturating_add(
+            T::DbWeight::get().reads_writes(3, 3).saturating_mul(T::MaxCandidates::get().into())
+        ).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn unlock_voting_interest(
+            origin: OriginFor<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            let locked = VotingLocks::<T>::get(&voter).unwrap_or_else(Zero::zero);
+            ensure!(
+                !amount.is_zero() && amount <= locked,
+                Error::<T>::UnlockingMoreThanLocked
+            );
+            let new_locked = locked.saturating_sub(amount);
+            let votes = Self::calculate_voting_interests(locked)
+                .saturating_sub(Self::calculate_voting_interests(new_locked));
+
+            let unlock_session =
+                CurrentSessionIndex::<T>::get().saturating_add(T::UnbondingPeriod::get());
+            VotingUnlocking::<T>::try_mutate(&voter, |chunks| {
+                match chunks.iter_mut().find(|chunk| chunk.unlock_session == unlock_session) {
+                    Some(chunk) => chunk.value = chunk.value.saturating_add(amount),
+                    None => chunks
+                        .try_push(UnlockChunk { value: amount, unlock_session })
+                        .map_err(|_| Error::<T>::TooManyUnlockingChunks)?,
+                }
+                Ok::<(), Error<T>>(())
+            })?;
+            if new_locked.is_zero() {
+                VotingLocks::<T>::remove(&voter);
             } else {
-                ensure_root(origin)?;
+                VotingLocks::<T>::insert(&voter, new_locked);
+            }
+            Self::reaffirm(&voter);
+            Self::remove_voting_interest(&voter, votes);
+            Self::deposit_event(Event::VotingInterestUnbonding(
+                voter,
+                amount,
+                votes,
+                unlock_session,
+            ));
+            Ok(())
+        }
+
+        /// release the caller's voting tokens whose unbonding period has passed
+        #[pallet::call_index(11)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 2))]
+        pub fn withdraw_unlocked_voting_tokens(origin: OriginFor<T>) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            let current_index = CurrentSessionIndex::<T>::get();
+            let mut chunks = VotingUnlocking::<T>::get(&voter);
+            let mut withdrawn: BalanceOf<T> = Zero::zero();
+            chunks.retain(|chunk| {
+                if chunk.unlock_session <= current_index {
+                    withdrawn = withdrawn.saturating_add(chunk.value);
+                    false
+                } else {
+                    true
+                }
+            });
+            ensure!(!withdrawn.is_zero(), Error::<T>::NothingToWithdraw);
+            if chunks.is_empty() {
+                VotingUnlocking::<T>::remove(&voter);
+            } else {
+                VotingUnlocking::<T>::insert(&voter, chunks);
+            }
+            Self::update_voting_lock(&voter);
+            Self::deposit_event(Event::UnlockedVotingTokensWithdrawn(voter, withdrawn));
+            Ok(())
+        }
+
+        /// slash the whole bond of a misbehaving candidate and remove its candidacy
+        ///
+        /// a candidate that was already removed can be slashed until its bond is withdrawn.
+        #[pallet::call_index(12)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(5, 5).saturating_add(
+            T::DbWeight::get().reads_writes(3, 3).saturating_mul(T::MaxCandidates::get().into())
+        ))]
+        pub fn slash_candidacy_bond(
+            origin: OriginFor<T>,
+            candidate: T::AccountId,
+        ) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            let is_candidate = Self::is_valid_candidate(&candidate);
+            let unbonding = UnbondingCandidacyBonds::<T>::take(&candidate)
+                .map(|chunk| chunk.value)
+                .unwrap_or_else(Zero::zero);
+            if !is_candidate && unbonding.is_zero() {
+                return Err(Error::<T>::CandidateDoesNotExist.into());
+            }
+            if is_candidate {
+                Self::do_remove_candidate(&candidate);
             }
-            PalletAdmin::<T>::put(new_admin);
+            let bond = CandidacyBonds::<T>::take(&candidate)
+                .unwrap_or_else(Zero::zero)
+                .saturating_add(unbonding);
+            if !bond.is_zero() {
+                let (imbalance, _) = T::Currency::slash_reserved(&candidate, bond);
+                T::OnCandidacyBondSlashed::on_unbalanced(imbalance);
+                Self::deposit_event(Event::CandidacyBondSlashed(candidate.clone(), bond));
+            }
+            if is_candidate {
+                Self::deposit_event(Event::CandidacyRemoved(candidate));
+            }
+            Ok(())
+        }
+
+        /// restore the caller's decayed delegations to full weight
+        #[pallet::call_index(13)]
+        #[pallet::weight(T::DbWeight::get().reads(1).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn reaffirm_votes(origin: OriginFor<T>) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            ensure!(
+                UsersVotingInterests::<T>::contains_key(&voter),
+                Error::<T>::DelegatorHasNoVotingCapacity
+            );
+            Self::reaffirm(&voter);
+            Self::deposit_event(Event::VotesReaffirmed(voter));
+            Ok(())
+        }
+
+        /// bring the decay of `voter`'s delegations up to date in the candidate totals
+        ///
+        /// `on_idle` settles every voter once per session, this settles one voter at once.
+        /// anyone may settle a stale voter.
+        #[pallet::call_index(14)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 2).saturating_add(
+            T::DbWeight::get().reads_writes(2, 2).saturating_mul(T::MaxCandidates::get().into())
+        ))]
+        pub fn settle_vote_decay(origin: OriginFor<T>, voter: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(
+                UsersVotingInterests::<T>::contains_key(&voter),
+                Error::<T>::DelegatorHasNoVotingCapacity
+            );
+            let decay = Self::settle_decay(&voter);
+            Self::deposit_event(Event::VoteDecaySettled(voter, decay));
+            Ok(())
+        }
+
+        /// set the votes the calling candidate delegates to itself from its own voting interest
+        ///
+        /// candidates need at least `MinimumSelfStake` to be elected. a candidate can always
+        /// back itself, even when it has `MaxSupportersPerCandidate` other supporters.
+        #[pallet::call_index(15)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(6, 6).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn declare_self_stake(origin: OriginFor<T>, votes: u64) -> DispatchResult {
+            let candidate = ensure_signed(origin)?;
+            if !Self::is_valid_candidate(&candidate) {
+                return Err(Error::<T>::CandidateDoesNotExist.into());
+            }
+            let voting_interest = match UsersVotingInterests::<T>::get(&candidate) {
+                Some(voting_interest) => voting_interest,
+                None => return Err(Error::<T>::DelegatorHasNoVotingCapacity.into()),
+            };
+            let self_stake = Self::self_stake(&candidate);
+            if votes > self_stake {
+                let available_votes = voting_interest
+                    .total
+                    .saturating_sub(voting_interest.delegated);
+                if votes - self_stake > available_votes {
+                    return Err(Error::<T>::DelegatorHasInsufficientVotes.into());
+                }
+                Self::reaffirm(&candidate);
+                Self::add_votes_to_candidate(&candidate, &candidate, votes - self_stake);
+            } else if votes < self_stake {
+                Self::reaffirm(&candidate);
+                Self::remove_votes_from_candidate(&candidate, &candidate, self_stake - votes);
+            }
+            Self::deposit_event(Event::SelfStakeDeclared(candidate, votes));
+            Ok(())
+        }
+
+        /// set the caller's public details. `name` and `sharing_percent` have their own calls.
+        #[pallet::call_index(17)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn update_candidate_details(
+            origin: OriginFor<T>,
+            website: BoundedVec<u8, ConstU32<128>>,
+            contact: BoundedVec<u8, ConstU32<128>>,
+            region: BoundedVec<u8, ConstU32<64>>,
+            telemetry_url: BoundedVec<u8, ConstU32<128>>,
+            identity: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let candidate = ensure_signed(origin)?;
+            if !Self::is_valid_candidate(&candidate) {
+                return Err(Error::<T>::CandidateDoesNotExist.into());
+            }
+            NodeMetadata::<T>::try_mutate(&candidate, |node_metadata| {
+                let node_metadata = match node_metadata {
+                    Some(node_metadata) => node_metadata,
+                    None => return Err(Error::<T>::ErrorGettingNodeMetadata),
+                };
+                node_metadata.website = website;
+                node_metadata.contact = contact;
+                node_metadata.region = region;
+                node_metadata.telemetry_url = telemetry_url;
+                node_metadata.identity = identity;
+                Ok(())
+            })?;
+            Ok(())
+        }
+
+        /// replace all of the caller's delegations with `targets`
+        ///
+        /// candidates missing from `targets` lose the caller's votes. the targets may add up to
+        /// at most the caller's voting interest.
+        #[pallet::call_index(16)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 1).saturating_add(
+            T::DbWeight::get().reads_writes(5, 4).saturating_mul(
+                T::MaxCandidates::get().saturating_mul(2).into()
+            )
+        ).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn rebalance_delegations(
+            origin: OriginFor<T>,
+            targets: Vec<ValidatorDelegations<T>>,
+        ) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            Self::do_rebalance_delegations(voter, targets)
+        }
+
+        /// let `manager` delegate the caller's votes, or stop any manager with `None`
+        ///
+        /// a manager can move the voter's votes between candidates but not transfer them
+        #[pallet::call_index(18)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(0, 1))]
+        pub fn set_vote_manager(
+            origin: OriginFor<T>,
+            manager: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            match manager {
+                Some(manager) => {
+                    VoteManagers::<T>::insert(&voter, &manager);
+                    Self::deposit_event(Event::VoteManagerSet(voter, manager));
+                }
+                None => {
+                    VoteManagers::<T>::remove(&voter);
+                    Self::deposit_event(Event::VoteManagerRemoved(voter));
+                }
+            }
+            Ok(())
+        }
+
+        /// `delegate_votes` on behalf of `voter`, who made the caller their vote manager
+        #[pallet::call_index(19)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn delegate_votes_for(
+            origin: OriginFor<T>,
+            voter: T::AccountId,
+            delegations: Vec<ValidatorDelegations<T>>,
+        ) -> DispatchResult {
+            let manager = ensure_signed(origin)?;
+            Self::ensure_vote_manager(&manager, &voter)?;
+            Self::do_delegate_votes(voter, delegations)
+        }
+
+        /// `rebalance_delegations` on behalf of `voter`, who made the caller their vote manager
+        #[pallet::call_index(20)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 1).saturating_add(
+            T::DbWeight::get().reads_writes(5, 4).saturating_mul(
+                T::MaxCandidates::get().saturating_mul(2).into()
+            )
+        ).saturating_add(
+            Pallet::<T>::reaffirm_weight()
+        ))]
+        pub fn rebalance_delegations_for(
+            origin: OriginFor<T>,
+            voter: T::AccountId,
+            targets: Vec<ValidatorDelegations<T>>,
+        ) -> DispatchResult {
+            let manager = ensure_signed(origin)?;
+            Self::ensure_vote_manager(&manager, &voter)?;
+            Self::do_rebalance_delegations(voter, targets)
+        }
+
+        /// give `votes` of the caller's undelegated voting interest to `to`
+        ///
+        /// only available when `AllowVotingInterestTransfers` is set and `VotingInterestMode`
+        /// is `Burn`, as locked voting interest must stay with the tokens backing it
+        #[pallet::call_index(21)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn transfer_voting_interest(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            votes: u64,
+        ) -> DispatchResult {
+            let from = ensure_signed(origin)?;
+            ensure!(
+                T::AllowVotingInterestTransfers::get(),
+                Error::<T>::VotingInterestTransfersDisabled
+            );
+            Self::do_transfer_voting_interest(from, to, votes)
+        }
+
+        /// move `votes` of `from`'s undelegated voting interest to `to`
+        #[pallet::call_index(22)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn force_transfer_voting_interest(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+            votes: u64,
+        ) -> DispatchResult {
+            T::GovernanceOrigin::ensure_origin(origin)?;
+            Self::do_transfer_voting_interest(from, to, votes)
+        }
+
+        /// take over the pallet admin role proposed to the caller
+        #[pallet::call_index(23)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn accept_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let new_admin = ensure_signed(origin)?;
+            let old_admin = PalletAdminHandover::<T>::accept(
+                new_admin.clone(),
+                frame_system::Pallet::<T>::block_number(),
+            )
+            .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminChanged(old_admin, new_admin));
+            Ok(())
+        }
+
+        /// withdraw the pending pallet admin proposal, root or the admin
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+        pub fn cancel_pallet_admin(origin: OriginFor<T>) -> DispatchResult {
+            let caller = ensure_signed_or_root(origin)?;
+            let proposed = PalletAdminHandover::<T>::cancel(caller)
+                .map_err(AdminError::into_dispatch_error::<Error<T>>)?;
+            Self::deposit_event(Event::AdminProposalCancelled(proposed));
+            Ok(())
+        }
+
+        /// return the caller's candidacy bond once its unbonding period has passed
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 2))]
+        pub fn withdraw_candidacy_bond(origin: OriginFor<T>) -> DispatchResult {
+            let candidate = ensure_signed(origin)?;
+            let chunk = UnbondingCandidacyBonds::<T>::get(&candidate)
+                .ok_or(Error::<T>::NothingToWithdraw)?;
+            ensure!(
+                chunk.unlock_session <= CurrentSessionIndex::<T>::get(),
+                Error::<T>::CandidacyBondStillUnbonding
+            );
+            UnbondingCandidacyBonds::<T>::remove(&candidate);
+            let _ = T::Currency::unreserve(&candidate, chunk.value);
+            Self::deposit_event(Event::CandidacyBondReturned(candidate, chunk.value));
             Ok(())
         }
     }
@@ -415,15 +1167,514 @@ pub mod pallet {
             UsersVotingInterests::<T>::insert(delegator, voting_interest);
         }
 
+        fn ensure_vote_manager(manager: &T::AccountId, voter: &T::AccountId) -> DispatchResult {
+            if VoteManagers::<T>::get(voter).as_ref() != Some(manager) {
+                return Err(Error::<T>::NotVoteManager.into());
+            }
+            Ok(())
+        }
+
+        fn do_transfer_voting_interest(
+            from: T::AccountId,
+            to: T::AccountId,
+            votes: u64,
+        ) -> DispatchResult {
+            ensure!(
+                T::VotingInterestMode::get() == VotingInterestMode::Burn,
+                Error::<T>::WrongVotingInterestMode
+            );
+            ensure!(from != to, Error::<T>::CannotTransferToSelf);
+            let mut voting_interest = match UsersVotingInterests::<T>::get(&from) {
+                Some(voting_interest) => voting_interest,
+                None => return Err(Error::<T>::DelegatorHasNoVotingCapacity.into()),
+            };
+            let available_votes = voting_interest
+                .total
+                .saturating_sub(voting_interest.delegated);
+            if votes == 0 || votes > available_votes {
+                return Err(Error::<T>::DelegatorHasInsufficientVotes.into());
+            }
+            voting_interest.total = voting_interest.total.saturating_sub(votes);
+            UsersVotingInterests::<T>::insert(&from, voting_interest);
+            Self::add_voting_interest_internal(to.clone(), votes);
+            Self::deposit_event(Event::VotingInterestTransferred(from, to, votes));
+            Ok(())
+        }
+
+        fn do_delegate_votes(
+            delegator: T::AccountId,
+            delegations: Vec<ValidatorDelegations<T>>,
+        ) -> DispatchResult {
+            if delegations.len() == 0 {
+                return Err(Error::<T>::EmptyDelegationList.into());
+            }
+            if (delegations.len() as u32) > T::MaxCandidates::get() {
+                return Err(Error::<T>::DelegationListTooLarge.into());
+            }
+
+            let maybe_voting_interest = UsersVotingInterests::<T>::get(delegator.clone());
+            if maybe_voting_interest.is_none() {
+                return Err(Error::<T>::DelegatorHasNoVotingCapacity.into());
+            }
+            let voting_interest = maybe_voting_interest.unwrap();
+            Self::validate_delegations(&delegator, &voting_interest, &delegations)?;
+            Self::reaffirm(&delegator);
+            let _ = Self::delegate_votes_to_candidates(&delegator, delegations);
+            Self::deposit_event(Event::VotesDelegatedBy(delegator));
+            Ok(())
+        }
+
+        fn do_rebalance_delegations(
+            voter: T::AccountId,
+            targets: Vec<ValidatorDelegations<T>>,
+        ) -> DispatchResult {
+            if (targets.len() as u32) > T::MaxCandidates::get() {
+                return Err(Error::<T>::DelegationListTooLarge.into());
+            }
+            let voting_interest = match UsersVotingInterests::<T>::get(&voter) {
+                Some(voting_interest) => voting_interest,
+                None => return Err(Error::<T>::DelegatorHasNoVotingCapacity.into()),
+            };
+            let mut target_total: u64 = 0;
+            for (index, target) in targets.iter().enumerate() {
+                if targets[..index]
+                    .iter()
+                    .any(|other| other.candidate == target.candidate)
+                {
+                    return Err(Error::<T>::DuplicateCandidateInDelegations.into());
+                }
+                if target.votes > 0 {
+                    if !Self::is_valid_candidate(&target.candidate) {
+                        return Err(Error::<T>::CandidateDoesNotExist.into());
+                    }
+                    Self::ensure_can_support(&voter, &target.candidate)?;
+                }
+                target_total = target_total.saturating_add(target.votes);
+            }
+            if target_total > voting_interest.total {
+                return Err(Error::<T>::DelegatorHasInsufficientVotes.into());
+            }
+
+            Self::reaffirm(&voter);
+            let mut changes = Vec::new();
+            let current = UserToNodeVotesTotals::<T>::iter_prefix((voter.clone(),))
+                .collect::<Vec<(T::AccountId, u64)>>();
+            for (candidate, delegated) in current {
+                let target = targets
+                    .iter()
+                    .find(|target| target.candidate == candidate)
+                    .map_or(0, |target| target.votes);
+                if target < delegated {
+                    Self::remove_votes_from_candidate(&voter, &candidate, delegated - target);
+                    changes.push((candidate, delegated, target));
+                }
+            }
+            for target in targets {
+                let delegated =
+                    UserToNodeVotesTotals::<T>::get((voter.clone(), target.candidate.clone()));
+                if target.votes > delegated {
+                    Self::add_votes_to_candidate(
+                        &voter,
+                        &target.candidate,
+                        target.votes - delegated,
+                    );
+                    changes.push((target.candidate, delegated, target.votes));
+                }
+            }
+            Self::deposit_event(Event::DelegationsRebalanced(voter, changes));
+            Ok(())
+        }
+
+        /// share of `voter`'s delegated votes counted in candidate totals
+        pub fn vote_retention(voter: &T::AccountId) -> Perbill {
+            Perbill::one().saturating_sub(AppliedVoteDecay::<T>::get(voter))
+        }
+
+        /// decay `voter`'s delegations should carry now, whether settled or not
+        pub fn pending_vote_decay(voter: &T::AccountId) -> Perbill {
+            let last_affirmed = match VoterLastAffirmed::<T>::get(voter) {
+                Some(session) => session,
+                None => return AppliedVoteDecay::<T>::get(voter),
+            };
+            let elapsed = CurrentSessionIndex::<T>::get().saturating_sub(last_affirmed);
+            let retained = Perbill::one()
+                .saturating_sub(T::VoteDecayRate::get())
+                .saturating_pow(elapsed as usize);
+            Perbill::one().saturating_sub(retained)
+        }
+
+        /// apply the decay owed by `voter` to the totals of the candidates they back
+        fn settle_decay(voter: &T::AccountId) -> Perbill {
+            if !VoterLastAffirmed::<T>::contains_key(voter) {
+                VoterLastAffirmed::<T>::insert(voter, CurrentSessionIndex::<T>::get());
+            }
+            let decay = Self::pending_vote_decay(voter);
+            Self::rescale_delegations(voter, decay);
+            decay
+        }
+
+        /// settle the decay of as many voters as `remaining_weight` and `MaxCleanupPerBlock`
+        /// allow, continuing the sweep of the current session. returns the weight used.
+        fn settle_vote_decay_batch(remaining_weight: Weight) -> Weight {
+            if T::VoteDecayRate::get().is_zero() {
+                return Weight::zero();
+            }
+            let base_weight = T::DbWeight::get().reads_writes(3, 2);
+            let weight_per_voter = T::DbWeight::get().reads_writes(4, 2).saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(2, 2)
+                    .saturating_mul(T::MaxCandidates::get().into()),
+            );
+            if remaining_weight.any_lt(base_weight.saturating_add(weight_per_voter)) {
+                return Weight::zero();
+            }
+            let current_session = CurrentSessionIndex::<T>::get();
+            let (session, voters) = match VoteDecayCursor::<T>::get() {
+                Some((session, last)) => (
+                    session,
+                    UsersVotingInterests::<T>::iter_keys_from(
+                        UsersVotingInterests::<T>::hashed_key_for(&last),
+                    ),
+                ),
+                None if VoteDecaySweptSession::<T>::get() == Some(current_session) => {
+                    return T::DbWeight::get().reads(3);
+                }
+                None => (current_session, UsersVotingInterests::<T>::iter_keys()),
+            };
+            let affordable = remaining_weight
+                .saturating_sub(base_weight)
+                .ref_time()
+                .checked_div(weight_per_voter.ref_time())
+                .unwrap_or(u64::MAX);
+            let limit = affordable.min(T::MaxCleanupPerBlock::get().into()) as usize;
+
+            let batch = voters.take(limit).collect::<Vec<T::AccountId>>();
+            for voter in batch.iter() {
+                Self::settle_decay(voter);
+            }
+            let settled = batch.len();
+            match batch.last() {
+                Some(last) if settled == limit => {
+                    VoteDecayCursor::<T>::put((session, last.clone()));
+                }
+                _ => {
+                    VoteDecayCursor::<T>::kill();
+                    VoteDecaySweptSession::<T>::put(session);
+                }
+            }
+            base_weight.saturating_add(weight_per_voter.saturating_mul(settled as u64))
+        }
+
+        /// weight of pruning a reaped voter with `delegations` delegations, at most one per
+        /// candidate
+        pub fn prune_voter_weight(delegations: u32) -> Weight {
+            T::DbWeight::get().reads_writes(1, 4).saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(7, 6)
+                    .saturating_mul(delegations.into()),
+            )
+        }
+
+        /// most weight `reaffirm` takes, rewriting the candidate totals of every delegation
+        pub fn reaffirm_weight() -> Weight {
+            T::DbWeight::get().reads_writes(2, 2).saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(2, 2)
+                    .saturating_mul(T::MaxCandidates::get().into()),
+            )
+        }
+
+        /// restore `voter`'s delegations to full weight and restart their decay
+        fn reaffirm(voter: &T::AccountId) {
+            if T::VoteDecayRate::get().is_zero() && AppliedVoteDecay::<T>::get(voter).is_zero() {
+                return;
+            }
+            Self::rescale_delegations(voter, Perbill::zero());
+            VoterLastAffirmed::<T>::insert(voter, CurrentSessionIndex::<T>::get());
+        }
+
+        /// count `voter`'s delegations with `decay` taken off in the candidate totals
+        fn rescale_delegations(voter: &T::AccountId, decay: Perbill) {
+            let old_retained = Self::vote_retention(voter);
+            let new_retained = Perbill::one().saturating_sub(decay);
+            if old_retained == new_retained {
+                return;
+            }
+            let delegations = UserToNodeVotesTotals::<T>::iter_prefix((voter.clone(),))
+                .collect::<Vec<(T::AccountId, u64)>>();
+            for (candidate, votes) in delegations {
+                if let Some(candidate_votes) = NodeAccumulativeVotes::<T>::get(&candidate) {
+                    let candidate_votes = candidate_votes
+                        .saturating_sub(old_retained.mul_floor(votes))
+                        .saturating_add(new_retained.mul_floor(votes));
+                    Self::set_candidate_votes(&candidate, candidate_votes);
+                }
+            }
+            if decay.is_zero() {
+                AppliedVoteDecay::<T>::remove(voter);
+            } else {
+                AppliedVoteDecay::<T>::insert(voter, decay);
+            }
+        }
+
+        /// the only place candidate vote totals are written, so `SortedCandidates` stays in order
+        ///
+        /// `submit_candidacy` keeps the candidates within `MaxCandidates`. should there be more,
+        /// e.g. from genesis, the list keeps the ones with the most votes and a candidate
+        /// outranking the last entry takes its place.
+        pub(crate) fn set_candidate_votes(candidate: &T::AccountId, votes: u64) {
+            NodeAccumulativeVotes::<T>::insert(candidate, votes);
+            SortedCandidates::<T>::mutate(|sorted| {
+                sorted.retain(|(account, _)| account != candidate);
+                let position = sorted.partition_point(|(_, other_votes)| *other_votes >= votes);
+                let _ = sorted.force_insert_keep_left(position, (candidate.clone(), votes));
+            });
+        }
+
+        fn remove_candidate_votes(candidate: &T::AccountId) {
+            NodeAccumulativeVotes::<T>::remove(candidate);
+            SortedCandidates::<T>::mutate(|sorted| {
+                sorted.retain(|(account, _)| account != candidate)
+            });
+        }
+
+        /// withdraw all support from a candidate and drop its candidacy
+        ///
+        /// support is returned to voters by `on_idle` through `CandidateCleanupQueue`, or at once
+        /// if the queue is full.
+        fn do_remove_candidate(candidate: &T::AccountId) {
+            Self::remove_candidate_votes(candidate);
+            CurrentNumberOfCandidatesNodes::<T>::mutate(|count| *count = count.saturating_sub(1));
+            NodeMetadata::<T>::remove(candidate);
+            CandidateRegisteredAt::<T>::remove(candidate);
+            ValidatorPerformance::<T>::remove(candidate);
+            DemotedValidators::<T>::remove(candidate);
+            PendingSharingPercent::<T>::remove(candidate);
+            let queued = CandidateCleanupQueue::<T>::mutate(|queue| {
+                queue.contains(candidate) || queue.try_push(candidate.clone()).is_ok()
+            });
+            if !queued {
+                let support_to_remove =
+                    NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),))
+                        .collect::<Vec<(T::AccountId, u64)>>();
+                for (supporter, delegated_votes) in support_to_remove {
+                    Self::remove_votes_from_candidate(&supporter, candidate, delegated_votes);
+                }
+                CandidateSupporterCount::<T>::remove(candidate);
+            }
+        }
+
+        /// move a removed candidate's bond into `UnbondingCandidacyBonds`
+        ///
+        /// a bond still unbonding from an earlier candidacy is added to it and waits out the
+        /// new period.
+        fn unbond_candidacy_bond(candidate: &T::AccountId) {
+            if let Some(bond) = CandidacyBonds::<T>::take(candidate) {
+                let unlock_session = CurrentSessionIndex::<T>::get()
+                    .saturating_add(T::CandidacyBondUnbondingPeriod::get());
+                let value = UnbondingCandidacyBonds::<T>::get(candidate)
+                    .map_or(bond, |chunk| chunk.value.saturating_add(bond));
+                UnbondingCandidacyBonds::<T>::insert(
+                    candidate,
+                    UnlockChunk { value, unlock_session },
+                );
+                Self::deposit_event(Event::CandidacyBondUnbonding(
+                    candidate.clone(),
+                    bond,
+                    unlock_session,
+                ));
+            }
+        }
+
+        /// evict up to `EvictionCount` candidates, fewest votes first
+        ///
+        /// a candidate past its `CandidateGracePeriod` can be evicted if it has fewer than
+        /// `MinimumCandidateVotes`, or if the candidate list is full. `validators` are never
+        /// evicted. the bonds of evicted candidates start unbonding.
+        fn evict_candidates(session_index: SessionIndex, validators: &[T::AccountId]) {
+            let max_candidates: u64 = T::MaxCandidates::get().into();
+            let weight = T::DbWeight::get()
+                .reads(max_candidates.saturating_add(1))
+                .saturating_add(Self::sorted_candidates_weight())
+                .saturating_add(
+                    T::DbWeight::get()
+                        .reads_writes(6, 11)
+                        .saturating_mul(T::EvictionCount::get().into()),
+                );
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                weight,
+                DispatchClass::Mandatory,
+            );
+            let at_capacity =
+                CurrentNumberOfCandidatesNodes::<T>::get() >= T::MaxCandidates::get();
+            let grace_period = T::CandidateGracePeriod::get();
+            let to_evict = Self::get_sorted_candidates_with_votes()
+                .into_iter()
+                .rev()
+                .filter(|(candidate, votes)| {
+                    let registered_at = CandidateRegisteredAt::<T>::get(candidate).unwrap_or(0);
+                    registered_at.saturating_add(grace_period) <= session_index
+                        && (at_capacity || *votes < T::MinimumCandidateVotes::get())
+                        && !validators.contains(candidate)
+                })
+                .take(T::EvictionCount::get() as usize)
+                .collect::<Vec<(T::AccountId, u64)>>();
+            for (candidate, votes) in to_evict {
+                Self::do_remove_candidate(&candidate);
+                Self::unbond_candidacy_bond(&candidate);
+                Self::deposit_event(Event::CandidateEvicted(candidate, votes));
+            }
+        }
+
+        /// return delegations of removed candidates to their voters, within `remaining_weight`
+        /// and `MaxCleanupPerBlock`
+        fn cleanup_removed_candidates(remaining_weight: Weight) -> Weight {
+            let base_weight = T::DbWeight::get().reads_writes(1, 2);
+            let weight_per_supporter = T::DbWeight::get().reads_writes(4, 4);
+            if remaining_weight.any_lt(base_weight.saturating_add(weight_per_supporter)) {
+                return Weight::zero();
+            }
+            let mut queue = CandidateCleanupQueue::<T>::get();
+            let candidate = match queue.first() {
+                Some(candidate) => candidate.clone(),
+                None => return T::DbWeight::get().reads(1),
+            };
+            let affordable = remaining_weight
+                .saturating_sub(base_weight)
+                .ref_time()
+                .checked_div(weight_per_supporter.ref_time())
+                .unwrap_or(u64::MAX);
+            let limit = affordable.min(T::MaxCleanupPerBlock::get().into()) as usize;
+
+            let support_to_remove = NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),))
+                .take(limit)
+                .collect::<Vec<(T::AccountId, u64)>>();
+            let processed = support_to_remove.len();
+            for (supporter, delegated_votes) in support_to_remove {
+                Self::remove_votes_from_candidate(&supporter, &candidate, delegated_votes);
+            }
+            if processed < limit {
+                queue.remove(0);
+                CandidateCleanupQueue::<T>::put(queue);
+                CandidateSupporterCount::<T>::remove(&candidate);
+                Self::deposit_event(Event::CandidateCleanupCompleted(candidate));
+            }
+            base_weight.saturating_add(weight_per_supporter.saturating_mul(processed as u64))
+        }
+
+        /// a voter not yet supporting `candidate` may only start if it has room for supporters
+        fn ensure_can_support(voter: &T::AccountId, candidate: &T::AccountId) -> DispatchResult {
+            if !NodeToUserVotesTotals::<T>::contains_key((candidate.clone(), voter.clone()))
+                && CandidateSupporterCount::<T>::get(candidate)
+                    >= T::MaxSupportersPerCandidate::get()
+            {
+                return Err(Error::<T>::CandidateHasMaxSupporters.into());
+            }
+            Ok(())
+        }
+
+        /// tokens held under the voting lock, both active and unbonding
+        pub fn total_voting_lock(voter: &T::AccountId) -> BalanceOf<T> {
+            VotingUnlocking::<T>::get(voter).iter().fold(
+                VotingLocks::<T>::get(voter).unwrap_or_else(Zero::zero),
+                |total, chunk| total.saturating_add(chunk.value),
+            )
+        }
+
+        fn update_voting_lock(voter: &T::AccountId) {
+            let total = Self::total_voting_lock(voter);
+            if total.is_zero() {
+                T::LockableCurrency::remove_lock(T::VotingLockIdentifier::get(), voter);
+            } else {
+                T::LockableCurrency::set_lock(
+                    T::VotingLockIdentifier::get(),
+                    voter,
+                    total,
+                    WithdrawReasons::all(),
+                );
+            }
+        }
+
+        /// take `votes` from a voter's voting interest
+        ///
+        /// delegations exceeding what remains are reduced in proportion to their size.
+        fn remove_voting_interest(voter: &T::AccountId, votes: u64) {
+            let mut voting_interest = match UsersVotingInterests::<T>::get(voter) {
+                Some(voting_interest) => voting_interest,
+                None => return,
+            };
+            voting_interest.total = voting_interest.total.saturating_sub(votes);
+            let delegated = voting_interest.delegated;
+            let excess = delegated.saturating_sub(voting_interest.total);
+            UsersVotingInterests::<T>::insert(voter, voting_interest);
+            if excess == 0 {
+                return;
+            }
+
+            let ratio = Perquintill::from_rational(excess, delegated);
+            let mut removals = UserToNodeVotesTotals::<T>::iter_prefix((voter.clone(),))
+                .map(|(candidate, votes)| (candidate, votes, ratio.mul_floor(votes)))
+                .collect::<Vec<(T::AccountId, u64, u64)>>();
+            // rounding down leaves a few votes over, take them from the first delegations
+            let mut remainder =
+                excess.saturating_sub(removals.iter().map(|removal| removal.2).sum::<u64>());
+            for removal in removals.iter_mut() {
+                if remainder == 0 {
+                    break;
+                }
+                let extra = removal.1.saturating_sub(removal.2).min(remainder);
+                removal.2 = removal.2.saturating_add(extra);
+                remainder = remainder.saturating_sub(extra);
+            }
+            for (candidate, _, votes) in removals {
+                if votes > 0 {
+                    Self::remove_votes_from_candidate(voter, &candidate, votes);
+                }
+            }
+        }
+
+        /// the candidates by votes, most first, with every voter's pending decay counted
+        ///
+        /// `SortedCandidates` holds the settled totals, which lag behind while `on_idle` has not
+        /// reached every voter. with decay enabled each candidate's supporters are read, at most
+        /// `MaxCandidates` times `MaxSupportersPerCandidate` entries.
         pub fn get_sorted_candidates_with_votes() -> Vec<(T::AccountId, u64)> {
-            let mut candidates =
-                NodeAccumulativeVotes::<T>::iter().collect::<Vec<(T::AccountId, u64)>>();
-            candidates.sort_by(|a, b| b.1.cmp(&a.1));
-            let mut sorted_candidates =
-                candidates.into_iter().collect::<Vec<(T::AccountId, u64)>>();
+            let sorted = SortedCandidates::<T>::get().into_inner();
+            if T::VoteDecayRate::get().is_zero() {
+                return sorted;
+            }
+            let mut ranked = sorted
+                .into_iter()
+                .map(|(candidate, _)| {
+                    let votes = Self::decayed_candidate_votes(&candidate);
+                    (candidate, votes)
+                })
+                .collect::<Vec<(T::AccountId, u64)>>();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1));
+            ranked
+        }
 
-            sorted_candidates.truncate(T::MaxCandidates::get() as usize);
-            sorted_candidates
+        /// reads made by `get_sorted_candidates_with_votes`
+        pub fn sorted_candidates_weight() -> Weight {
+            let supporter_reads = if T::VoteDecayRate::get().is_zero() {
+                0
+            } else {
+                u64::from(T::MaxCandidates::get())
+                    .saturating_mul(T::MaxSupportersPerCandidate::get().into())
+                    .saturating_mul(2)
+            };
+            T::DbWeight::get().reads(supporter_reads.saturating_add(1))
+        }
+
+        /// `candidate`'s votes with the decay each supporter owes now, settled or not
+        pub fn decayed_candidate_votes(candidate: &T::AccountId) -> u64 {
+            NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),)).fold(
+                0u64,
+                |total, (voter, votes)| {
+                    let retained = Perbill::one().saturating_sub(Self::pending_vote_decay(&voter));
+                    total.saturating_add(retained.mul_floor(votes))
+                },
+            )
         }
 
         pub fn get_user_supported_nodes(delegator: T::AccountId) -> Vec<T::AccountId> {
@@ -452,8 +1703,8 @@ pub mod pallet {
             delegator: T::AccountId,
             candidate: T::AccountId,
         ) -> Option<Perquintill> {
-            let user_to_node_votes =
-                UserToNodeVotesTotals::<T>::get((delegator.clone(), candidate.clone()));
+            let user_to_node_votes = Self::vote_retention(&delegator)
+                .mul_floor(UserToNodeVotesTotals::<T>::get((delegator.clone(), candidate.clone())));
             if user_to_node_votes == 0 {
                 return None;
             }
@@ -466,6 +1717,44 @@ pub mod pallet {
             Some(ratio)
         }
 
+        /// share of `candidate`'s rewards going to `delegator`: their part of its votes times
+        /// the share it passes on to supporters
+        pub fn projected_reward_share(
+            delegator: T::AccountId,
+            candidate: T::AccountId,
+        ) -> Option<Perquintill> {
+            let sharing_percent = Self::get_node_sharing_percent(candidate.clone())?;
+            let support_ratio = Self::get_user_support_ratio(delegator, candidate)?;
+            Some(Perquintill::from_percent(sharing_percent.into()) * support_ratio)
+        }
+
+        /// up to `count` candidates from position `start` of the ranking
+        pub fn get_candidates_page(start: u32, count: u32) -> Vec<CandidateInfo<T::AccountId>> {
+            SortedCandidates::<T>::get()
+                .into_iter()
+                .skip(start as usize)
+                .take(count as usize)
+                .map(|(account_id, votes)| CandidateInfo {
+                    metadata: NodeMetadata::<T>::get(&account_id),
+                    account_id,
+                    votes,
+                })
+                .collect()
+        }
+
+        pub fn get_candidate_supporters(candidate: T::AccountId) -> Vec<(T::AccountId, u64)> {
+            NodeToUserVotesTotals::<T>::iter_prefix((candidate,)).collect()
+        }
+
+        pub fn get_voter_delegations(voter: T::AccountId) -> VoterDelegations<T::AccountId> {
+            let voting_interest = UsersVotingInterests::<T>::get(&voter).unwrap_or_default();
+            VoterDelegations {
+                delegations: UserToNodeVotesTotals::<T>::iter_prefix((voter,)).collect(),
+                total: voting_interest.total,
+                available: voting_interest.total.saturating_sub(voting_interest.delegated),
+            }
+        }
+
         pub fn get_validator_supporter_share(validator: &T::AccountId) -> u8 {
             let candidate_metadata = NodeMetadata::<T>::get(validator.clone());
             if candidate_metadata.is_none() {
@@ -475,59 +1764,142 @@ pub mod pallet {
             candidate_metadata.sharing_percent
         }
 
-        pub fn get_sorted_candidates() -> Option<Vec<T::AccountId>> {
-            let mut candidates =
-                NodeAccumulativeVotes::<T>::iter().collect::<Vec<(T::AccountId, u64)>>();
-            candidates.sort_by(|a, b| b.1.cmp(&a.1));
-            let mut sorted_candidates = candidates
+        /// votes `candidate` delegates to itself
+        pub fn self_stake(candidate: &T::AccountId) -> u64 {
+            NodeToUserVotesTotals::<T>::get((candidate.clone(), candidate.clone()))
+        }
+
+        /// sorted candidates that are not on standby and meet `MinimumSelfStake`, ranked with
+        /// pending vote decay counted
+        pub fn get_electable_candidates() -> Vec<T::AccountId> {
+            let current_index = CurrentSessionIndex::<T>::get();
+            Self::get_sorted_candidates_with_votes()
                 .into_iter()
                 .map(|(candidate, _)| candidate)
-                .collect::<Vec<T::AccountId>>();
+                .filter(|candidate| match DemotedValidators::<T>::get(candidate) {
+                    Some(until) => until < current_index,
+                    None => true,
+                })
+                .filter(|candidate| Self::self_stake(candidate) >= T::MinimumSelfStake::get())
+                .collect()
+        }
 
-            sorted_candidates.truncate(T::MaxCandidates::get() as usize);
-            match sorted_candidates.len() {
-                0 => None,
-                _ => Some(sorted_candidates),
+        /// record how the validators of `session` performed and demote those `DemotionRule`
+        /// finds underperforming
+        fn record_validator_performance(session: SessionIndex, validators: &[T::AccountId]) {
+            for validator in validators {
+                let performance =
+                    match T::ValidatorPerformanceProvider::session_performance(session, validator) {
+                        Some(performance) => performance,
+                        None => continue,
+                    };
+                let mut stats = ValidatorPerformance::<T>::get(validator);
+                stats.sessions_tracked = stats.sessions_tracked.saturating_add(1);
+                if performance.online {
+                    stats.sessions_online = stats.sessions_online.saturating_add(1);
+                }
+                stats.blocks_produced =
+                    stats.blocks_produced.saturating_add(performance.blocks_produced.into());
+                if T::DemotionRule::is_underperforming(&performance) {
+                    stats.consecutive_underperforming =
+                        stats.consecutive_underperforming.saturating_add(1);
+                } else {
+                    stats.consecutive_underperforming = 0;
+                }
+                if T::DemotionRule::should_demote(&stats) {
+                    stats.consecutive_underperforming = 0;
+                    let until = session.saturating_add(T::StandbyPeriod::get());
+                    DemotedValidators::<T>::insert(validator, until);
+                    Self::deposit_event(Event::ValidatorDemoted(validator.clone(), until));
+                }
+                ValidatorPerformance::<T>::insert(validator, stats);
             }
         }
 
-        fn call_burn_contract(
-            token_burner: T::AccountId,
-            voter: T::AccountId,
-            main_pool: T::AccountId,
-            amount: BalanceOf<T>,
-            burn_contract: T::AccountId,
-            weight: Weight,
-        ) -> Result<(), DispatchError> {
-            let decimals: BalanceOf<T> = T::CurrencySubUnits::get();
-            let burn_minimum: BalanceOf<T> = <BalanceOf<T>>::from(100u32).saturating_mul(decimals);
-            if amount < burn_minimum {
-                return Err(Error::<T>::BurnAmountMustBeGreaterThan100.into());
+        /// tier of `node` in the last ended session
+        pub fn node_tier(node: &T::AccountId) -> Option<NodeTier> {
+            NodeTiers::<T>::get()
+                .into_iter()
+                .find(|(tiered_node, _)| tiered_node == node)
+                .map(|(_, tier)| tier)
+        }
+
+        /// rank the candidates into tiers: `validators` are super nodes, split into thirds by
+        /// votes, the next `MaxStandbyNodes` candidates stand by and the rest are candidates
+        fn assign_node_tiers(validators: &[T::AccountId]) -> Vec<(T::AccountId, u64, NodeTier)> {
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                Self::sorted_candidates_weight(),
+                DispatchClass::Mandatory,
+            );
+            let ranked = Self::get_sorted_candidates_with_votes();
+            let super_nodes = ranked
+                .iter()
+                .filter(|(node, _)| validators.contains(node))
+                .count();
+            let mut super_rank = 0;
+            let mut standby_left = T::MaxStandbyNodes::get();
+            ranked
+                .into_iter()
+                .map(|(node, votes)| {
+                    let tier = if validators.contains(&node) {
+                        let sub_tier = match super_rank * 3 / super_nodes {
+                            0 => SuperNodeSubTier::Upper,
+                            1 => SuperNodeSubTier::Middle,
+                            _ => SuperNodeSubTier::Lower,
+                        };
+                        super_rank += 1;
+                        NodeTier::Super(sub_tier)
+                    } else if standby_left > 0 {
+                        standby_left -= 1;
+                        NodeTier::StandBy
+                    } else {
+                        NodeTier::Candidate
+                    };
+                    (node, votes, tier)
+                })
+                .collect()
+        }
+
+        /// apply the announced `sharing_percent` changes due by `session`
+        fn apply_sharing_percent_changes(session: SessionIndex) {
+            let due = PendingSharingPercent::<T>::iter()
+                .filter(|(_, change)| change.effective_session <= session)
+                .collect::<Vec<(T::AccountId, SharingPercentChange)>>();
+            for (candidate, change) in due {
+                PendingSharingPercent::<T>::remove(&candidate);
+                NodeMetadata::<T>::mutate(&candidate, |node_metadata| {
+                    if let Some(node_metadata) = node_metadata {
+                        node_metadata.sharing_percent = change.sharing_percent;
+                        node_metadata.index_of_last_percent_change = session;
+                    }
+                });
+                Self::deposit_event(Event::SharingPercentChanged(
+                    candidate,
+                    change.sharing_percent,
+                ));
             }
-            //0xb1efc17b
-            let mut selector: Vec<u8> = [0xb1, 0xef, 0xc1, 0x7b].into();
-            let mut encoded_voter: Vec<u8> = voter.encode();
-            let mut encoded_burn_contract: Vec<u8> = burn_contract.encode();
-            let mut data_for_contract_call = Vec::new();
-            data_for_contract_call.append(&mut selector);
-            data_for_contract_call.append(&mut encoded_voter);
-            data_for_contract_call.append(&mut encoded_burn_contract);
+        }
 
-            let contract_call_result = pallet_contracts::Pallet::<T>::bare_call(
-                token_burner,
-                main_pool,
-                amount,
-                weight,
-                None,
-                data_for_contract_call,
-                false,
-                pallet_contracts::Determinism::Enforced,
-            )
-            .result;
-            if let Err(e) = contract_call_result {
-                return Err(e);
+        /// take validators whose standby is over off `DemotedValidators`
+        fn release_demoted_validators(current_index: SessionIndex) {
+            let released = DemotedValidators::<T>::iter()
+                .filter(|(_, until)| *until < current_index)
+                .map(|(validator, _)| validator)
+                .collect::<Vec<T::AccountId>>();
+            for validator in released {
+                DemotedValidators::<T>::remove(validator);
+            }
+        }
+
+        pub fn get_sorted_candidates() -> Option<Vec<T::AccountId>> {
+            let sorted_candidates = SortedCandidates::<T>::get()
+                .into_iter()
+                .map(|(candidate, _)| candidate)
+                .collect::<Vec<T::AccountId>>();
+            match sorted_candidates.len() {
+                0 => None,
+                _ => Some(sorted_candidates),
             }
-            Ok(())
         }
 
         fn calculate_voting_interests(amount: BalanceOf<T>) -> u64 {
@@ -537,6 +1909,7 @@ pub mod pallet {
         }
 
         fn validate_delegations(
+            delegator: &T::AccountId,
             voting_interest: &VotingInterest,
             delegations: &Vec<ValidatorDelegations<T>>,
         ) -> Result<(), DispatchError> {
@@ -551,6 +1924,7 @@ pub mod pallet {
                 if !Self::is_valid_candidate(&delegation.candidate) {
                     return Err(Error::<T>::CandidateDoesNotExist.into());
                 }
+                Self::ensure_can_support(delegator, &delegation.candidate)?;
                 votes_to_distribute = votes_to_distribute.saturating_add(delegation.votes);
                 if votes_to_distribute > available_votes {
                     return Err(Error::<T>::DelegatorHasInsufficientVotes.into());
@@ -576,12 +1950,20 @@ pub mod pallet {
         }
 
         fn add_votes_to_candidate(delegator: &T::AccountId, candidate: &T::AccountId, votes: u64) {
-            let added_votes =
-                NodeAccumulativeVotes::<T>::mutate(candidate.clone(), |candidate_votes_opt| {
-                    let candidate_votes = candidate_votes_opt.unwrap();
-                    candidate_votes.saturating_add(votes)
+            if let Some(candidate_votes) = NodeAccumulativeVotes::<T>::get(candidate) {
+                let retained = Self::vote_retention(delegator);
+                let delegated =
+                    NodeToUserVotesTotals::<T>::get((candidate.clone(), delegator.clone()));
+                let candidate_votes = candidate_votes
+                    .saturating_sub(retained.mul_floor(delegated))
+                    .saturating_add(retained.mul_floor(delegated.saturating_add(votes)));
+                Self::set_candidate_votes(candidate, candidate_votes);
+            }
+            if !NodeToUserVotesTotals::<T>::contains_key((candidate.clone(), delegator.clone())) {
+                CandidateSupporterCount::<T>::mutate(candidate, |count| {
+                    *count = count.saturating_add(1)
                 });
-            NodeAccumulativeVotes::<T>::insert(candidate.clone(), added_votes);
+            }
 
             let candidate_support = NodeToUserVotesTotals::<T>::mutate(
                 (candidate.clone(), delegator.clone()),
@@ -615,18 +1997,21 @@ pub mod pallet {
             candidate: &T::AccountId,
             votes: u64,
         ) {
-            let candidate_votes =
-                NodeAccumulativeVotes::<T>::mutate(candidate.clone(), |candidate_votes_opt| {
-                    let candidate_votes = candidate_votes_opt.unwrap();
-                    candidate_votes.saturating_sub(votes)
-                });
-            NodeAccumulativeVotes::<T>::insert(candidate.clone(), candidate_votes);
-
             let delegated_votes =
                 NodeToUserVotesTotals::<T>::get((candidate.clone(), delegator.clone()));
+            if let Some(candidate_votes) = NodeAccumulativeVotes::<T>::get(candidate) {
+                let retained = Self::vote_retention(delegator);
+                let candidate_votes = candidate_votes
+                    .saturating_sub(retained.mul_floor(delegated_votes))
+                    .saturating_add(retained.mul_floor(delegated_votes.saturating_sub(votes)));
+                Self::set_candidate_votes(candidate, candidate_votes);
+            }
             if delegated_votes == votes {
                 let _ = NodeToUserVotesTotals::<T>::remove((candidate.clone(), delegator.clone()));
                 let _ = UserToNodeVotesTotals::<T>::remove((delegator.clone(), candidate.clone()));
+                CandidateSupporterCount::<T>::mutate(candidate, |count| {
+                    *count = count.saturating_sub(1)
+                });
             } else {
                 let candidate_support = NodeToUserVotesTotals::<T>::mutate(
                     (candidate.clone(), delegator.clone()),
@@ -661,58 +2046,59 @@ pub mod pallet {
             if sorted_candidates_opt.is_none() {
                 return None;
             }
-            let mut sorted_candidates = sorted_candidates_opt.unwrap();
+            let sorted_candidates = sorted_candidates_opt.unwrap();
 
-            let bounded_candidates: BoundedVec<T::AccountId, ConstU32<300>> =
-                BoundedVec::try_from(sorted_candidates.clone()).unwrap();
+            let bounded_candidates: BoundedVec<T::AccountId, T::MaxCandidates> =
+                BoundedVec::truncate_from(sorted_candidates);
             SessionNodeList::<T>::insert(new_index, bounded_candidates);
-            sorted_candidates.truncate(T::MaxValidatorNodes::get() as usize);
+            if let Some(expired) = new_index.checked_sub(T::SessionHistoryDepth::get()) {
+                SessionNodeList::<T>::remove(expired);
+            }
 
-            Some(sorted_candidates)
+            Self::release_demoted_validators(CurrentSessionIndex::<T>::get());
+            let to_elect = T::MaxValidatorNodes::get();
+            let validators = T::ElectionStrategy::elect(to_elect);
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                T::ElectionStrategy::weight(to_elect),
+                DispatchClass::Mandatory,
+            );
+            if validators.is_empty() {
+                return None;
+            }
+            ElectedValidators::<T>::insert(
+                new_index,
+                BoundedVec::<_, T::MaxValidatorNodes>::truncate_from(validators.clone()),
+            );
+            Some(validators)
         }
 
         fn start_session(start_index: SessionIndex) {
             let _ = CurrentSessionIndex::<T>::put(start_index);
             let _ = T::ReferendumManager::start_pending_votes(start_index);
-            let sorted_candidates_opt = Self::get_sorted_candidates();
-            if sorted_candidates_opt.is_none() {
+            Self::apply_sharing_percent_changes(start_index);
+            if Self::get_sorted_candidates().is_none() {
                 return;
             }
-            let mut sorted_candidates = sorted_candidates_opt.unwrap();
-
-            // if at max candidates, remove the bottom 12
-            if CurrentNumberOfCandidatesNodes::<T>::get() == T::MaxCandidates::get() {
-                if sorted_candidates.len() > (288 as usize) {
-                    let to_be_dropped: Vec<T::AccountId> =
-                        sorted_candidates.clone().drain(288..).collect();
-                    for candidate in to_be_dropped {
-                        NodeMetadata::<T>::remove(candidate.clone());
-                        let mut support_to_remove: Vec<(T::AccountId, u64)> = Vec::new();
-                        let mut prefix_iterator =
-                            NodeToUserVotesTotals::<T>::iter_prefix((candidate.clone(),));
-                        while let Some((supporter, delegated_votes)) = prefix_iterator.next() {
-                            support_to_remove.push((supporter, delegated_votes));
-                        }
-                        for support in support_to_remove {
-                            Self::remove_votes_from_candidate(&support.0, &candidate, support.1);
-                        }
-                    }
-                }
-            }
 
             // store validator stats
-            sorted_candidates.truncate(T::MaxValidatorNodes::get() as usize);
+            let validators = match ElectedValidators::<T>::take(start_index) {
+                Some(elected) => elected.into_inner(),
+                None => {
+                    let mut electable = Self::get_electable_candidates();
+                    electable.truncate(T::MaxValidatorNodes::get() as usize);
+                    electable
+                }
+            };
             let _ = CurrentValidatorVoteStats::<T>::drain();
-            for validator in sorted_candidates.iter() {
+            for validator in validators.iter() {
                 let total_votes_opt = NodeAccumulativeVotes::<T>::get(validator.clone());
                 if total_votes_opt.is_none() {
                     continue;
                 }
                 let total_votes = total_votes_opt.unwrap();
-                let self_votes = total_votes.saturating_sub(NodeToUserVotesTotals::<T>::get((
-                    validator.clone(),
-                    validator.clone(),
-                )));
+                let self_votes = Self::vote_retention(validator)
+                    .mul_floor(Self::self_stake(validator))
+                    .min(total_votes);
 
                 let _ = CurrentValidatorVoteStats::<T>::insert(
                     validator.clone(),
@@ -724,14 +2110,54 @@ pub mod pallet {
                     },
                 );
             }
+
+            Self::evict_candidates(start_index, &validators);
         }
 
         fn end_session(end_index: SessionIndex) {
-            let _ = CurrentValidatorVoteStats::<T>::drain();
-            let sorted_nodes_with_votes = Self::get_sorted_candidates_with_votes();
+            let validators = CurrentValidatorVoteStats::<T>::drain()
+                .map(|(validator, _)| validator)
+                .collect::<Vec<T::AccountId>>();
+            Self::record_validator_performance(end_index, &validators);
+            let tiered_nodes = Self::assign_node_tiers(&validators);
+            NodeTiers::<T>::put(BoundedVec::<_, T::MaxCandidates>::truncate_from(
+                tiered_nodes
+                    .iter()
+                    .map(|(node, _, tier)| (node.clone(), tier.clone()))
+                    .collect::<Vec<_>>(),
+            ));
 
-            let _ = T::NodeRewardManager::update_rewards(end_index, sorted_nodes_with_votes);
+            let _ = T::NodeRewardManager::update_rewards(end_index, tiered_nodes);
             let _ = T::ReferendumManager::end_active_votes(end_index);
         }
     }
+
+    /// called when `account` is reaped
+    ///
+    /// if `PruneReapedAccounts` is set, all votes delegated by `account` are withdrawn from
+    /// their candidates and its voting interest is removed.
+    impl<T: Config> OnKilledAccount<T::AccountId> for Pallet<T> {
+        fn on_killed_account(account: &T::AccountId) {
+            if !T::PruneReapedAccounts::get() || !UsersVotingInterests::<T>::contains_key(account)
+            {
+                return;
+            }
+            let delegations = UserToNodeVotesTotals::<T>::iter_prefix((account.clone(),))
+                .collect::<Vec<(T::AccountId, u64)>>();
+            // accounts are reaped inside whatever call or hook drained them, which does not
+            // budget for the delegations
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                Self::prune_voter_weight(delegations.len() as u32),
+                DispatchClass::Mandatory,
+            );
+            for (candidate, votes) in delegations {
+                Self::remove_votes_from_candidate(account, &candidate, votes);
+            }
+            UsersVotingInterests::<T>::remove(account);
+            VoterLastAffirmed::<T>::remove(account);
+            AppliedVoteDecay::<T>::remove(account);
+            VoteManagers::<T>::remove(account);
+            Self::deposit_event(Event::VotingInterestPruned(account.clone()));
+        }
+    }
 }
@@ -0,0 +1,107 @@
+use super::*;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+    weights::Weight,
+};
+use sp_std::collections::btree_map::BTreeMap;
+
+/// builds `SortedCandidates` from `NodeAccumulativeVotes` (storage version 1 to 2)
+pub struct MigrateToSortedCandidates<T>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToSortedCandidates<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() != 1 {
+            return T::DbWeight::get().reads(1);
+        }
+        let mut candidates =
+            NodeAccumulativeVotes::<T>::iter().collect::<Vec<(T::AccountId, u64)>>();
+        let read = candidates.len() as u64;
+        candidates.sort_by(|a, b| b.1.cmp(&a.1));
+        SortedCandidates::<T>::put(BoundedVec::<_, T::MaxCandidates>::truncate_from(candidates));
+        StorageVersion::new(2).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(read + 1, 2)
+    }
+}
+
+/// counts the supporters of every candidate into `CandidateSupporterCount` (storage version 2
+/// to 3)
+pub struct MigrateToSupporterCounts<T>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToSupporterCounts<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() != 2 {
+            return T::DbWeight::get().reads(1);
+        }
+        let mut counts: BTreeMap<T::AccountId, u32> = BTreeMap::new();
+        let mut read = 0u64;
+        for ((candidate, _), _) in NodeToUserVotesTotals::<T>::iter() {
+            read += 1;
+            let count = counts.entry(candidate).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+        let written = counts.len() as u64;
+        for (candidate, count) in counts {
+            CandidateSupporterCount::<T>::insert(candidate, count);
+        }
+        StorageVersion::new(3).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(read + 1, written + 1)
+    }
+}
+
+/// re-bounds `SessionNodeList` by `MaxCandidates` and drops sessions older than
+/// `SessionHistoryDepth` (storage version 3 to 4)
+pub struct MigrateToBoundedSessionNodeList<T>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToBoundedSessionNodeList<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() != 3 {
+            return T::DbWeight::get().reads(1);
+        }
+        let oldest_kept = CurrentSessionIndex::<T>::get()
+            .saturating_add(1)
+            .saturating_sub(T::SessionHistoryDepth::get());
+        let mut translated = 0u64;
+        SessionNodeList::<T>::translate::<Vec<T::AccountId>, _>(|session_index, nodes| {
+            translated += 1;
+            if session_index < oldest_kept {
+                return None;
+            }
+            Some(BoundedVec::truncate_from(nodes))
+        });
+        StorageVersion::new(4).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(translated + 2, translated + 1)
+    }
+}
+
+/// `NodeMetadataStruct` before storage version 5
+#[derive(Encode, Decode)]
+struct NodeMetadataV4 {
+    name: BoundedVec<u8, ConstU32<128>>,
+    sharing_percent: u8,
+    index_of_last_percent_change: u32,
+}
+
+/// adds the endpoint, region and identity fields to `NodeMetadata`, left empty (storage
+/// version 4 to 5)
+pub struct MigrateToExtendedNodeMetadata<T>(PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateToExtendedNodeMetadata<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() != 4 {
+            return T::DbWeight::get().reads(1);
+        }
+        let mut translated = 0u64;
+        NodeMetadata::<T>::translate::<NodeMetadataV4, _>(|_, old| {
+            translated += 1;
+            Some(NodeMetadataStruct {
+                name: old.name,
+                sharing_percent: old.sharing_percent,
+                index_of_last_percent_change: old.index_of_last_percent_change,
+                website: Default::default(),
+                contact: Default::default(),
+                region: Default::default(),
+                telemetry_url: Default::default(),
+                identity: None,
+            })
+        });
+        StorageVersion::new(5).put::<Pallet<T>>();
+        T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+    }
+}
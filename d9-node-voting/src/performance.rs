@@ -0,0 +1,54 @@
+use crate::types::{SessionPerformance, ValidatorPerformanceStats};
+use frame_support::pallet_prelude::*;
+use sp_staking::SessionIndex;
+
+/// reports how validators performed in a finished session
+pub trait ValidatorPerformanceProvider<AccountId> {
+    /// performance of `validator` in `session`, `None` if nothing is known
+    fn session_performance(
+        session: SessionIndex,
+        validator: &AccountId,
+    ) -> Option<SessionPerformance>;
+}
+
+impl<AccountId> ValidatorPerformanceProvider<AccountId> for () {
+    fn session_performance(_: SessionIndex, _: &AccountId) -> Option<SessionPerformance> {
+        None
+    }
+}
+
+/// decides when a validator is demoted to standby
+pub trait DemotionRule {
+    /// whether a session counts against the validator
+    fn is_underperforming(performance: &SessionPerformance) -> bool;
+    /// whether the validator should be demoted, given its stats after the latest session
+    fn should_demote(stats: &ValidatorPerformanceStats) -> bool;
+}
+
+/// never demotes
+impl DemotionRule for () {
+    fn is_underperforming(_: &SessionPerformance) -> bool {
+        false
+    }
+    fn should_demote(_: &ValidatorPerformanceStats) -> bool {
+        false
+    }
+}
+
+/// demotes a validator after `MaxSessions` underperforming sessions in a row
+///
+/// a session is underperforming if the validator was offline or produced fewer than
+/// `MinBlocks` blocks.
+pub struct ConsecutiveUnderperformance<MinBlocks, MaxSessions>(
+    PhantomData<(MinBlocks, MaxSessions)>,
+);
+impl<MinBlocks: Get<u32>, MaxSessions: Get<u32>> DemotionRule
+    for ConsecutiveUnderperformance<MinBlocks, MaxSessions>
+{
+    fn is_underperforming(performance: &SessionPerformance) -> bool {
+        !performance.online || performance.blocks_produced < MinBlocks::get()
+    }
+    fn should_demote(stats: &ValidatorPerformanceStats) -> bool {
+        stats.consecutive_underperforming >= MaxSessions::get()
+    }
+}
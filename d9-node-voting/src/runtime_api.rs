@@ -0,0 +1,25 @@
+use crate::types::{CandidateInfo, VoterDelegations};
+use codec::Codec;
+use sp_arithmetic::Perquintill;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// node-voting state for frontends
+    ///
+    /// `VoteStats` is the runtime's `ValidatorVoteStats`
+    pub trait NodeVotingApi<AccountId, VoteStats>
+    where
+        AccountId: Codec,
+        VoteStats: Codec,
+    {
+        /// up to `count` candidates from position `start` of the ranking, most votes first
+        fn sorted_candidates(start: u32, count: u32) -> Vec<CandidateInfo<AccountId>>;
+        /// (supporter, votes) of every account delegating to `candidate`
+        fn candidate_supporters(candidate: AccountId) -> Vec<(AccountId, u64)>;
+        fn voter_delegations(voter: AccountId) -> VoterDelegations<AccountId>;
+        /// vote stats of `candidate`, if it validates the current session
+        fn validator_vote_stats(candidate: AccountId) -> Option<VoteStats>;
+        /// share of `candidate`'s rewards `voter` would receive
+        fn projected_reward_share(voter: AccountId, candidate: AccountId) -> Option<Perquintill>;
+    }
+}
@@ -0,0 +1,165 @@
+use crate as pallet_d9_node_voting;
+use crate::*;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstBool, ConstU32, ConstU64, LockIdentifier},
+    weights::Weight,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
+};
+
+pub type AccountId = u64;
+type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        NodeVoting: pallet_d9_node_voting::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type RuntimeEvent = RuntimeEvent;
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ConstU32<10>;
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type MaxHolds = ();
+    type FreezeIdentifier = ();
+    type HoldIdentifier = ();
+    type MaxFreezes = ();
+}
+
+parameter_types! {
+    pub const VotingLockIdentifier: LockIdentifier = *b"d9voting";
+    pub static Mode: VotingInterestMode = VotingInterestMode::Burn;
+    pub static UsePhragmen: bool = false;
+    pub static MaxElectingVoters: u32 = 100;
+    pub static DecayRate: Perbill = Perbill::zero();
+    pub static OfflineValidators: Vec<AccountId> = vec![];
+    pub static MinSelfStake: u64 = 0;
+    pub static AllowTransfers: bool = false;
+    pub static MaxCleanup: u32 = 2;
+}
+
+/// validators in `OfflineValidators` were offline, every other validator produced 10 blocks
+pub struct TestPerformance;
+impl ValidatorPerformanceProvider<AccountId> for TestPerformance {
+    fn session_performance(_: SessionIndex, validator: &AccountId) -> Option<SessionPerformance> {
+        let online = !OfflineValidators::get().contains(validator);
+        Some(SessionPerformance {
+            online,
+            blocks_produced: if online { 10 } else { 0 },
+        })
+    }
+}
+
+/// switches between the two election strategies through `UsePhragmen`
+pub struct TestElection;
+impl ElectionStrategy<AccountId> for TestElection {
+    fn elect(to_elect: u32) -> Vec<AccountId> {
+        if UsePhragmen::get() {
+            SequentialPhragmen::<Test, MaxElectingVoters>::elect(to_elect)
+        } else {
+            TopVotes::<Test>::elect(to_elect)
+        }
+    }
+
+    fn weight(to_elect: u32) -> Weight {
+        SequentialPhragmen::<Test, MaxElectingVoters>::weight(to_elect)
+            .max(TopVotes::<Test>::weight(to_elect))
+    }
+}
+
+impl pallet_d9_node_voting::Config for Test {
+    type CurrencySubUnits = ConstU64<1>;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type MaxCandidates = ConstU32<10>;
+    type MaxValidatorNodes = ConstU32<2>;
+    type NodeRewardManager = ();
+    type ReferendumManager = ();
+    type BurnHandler = NativeBurnHandler<Test>;
+    type PruneReapedAccounts = ConstBool<true>;
+    type LockableCurrency = Balances;
+    type VotingLockIdentifier = VotingLockIdentifier;
+    type VotingInterestMode = Mode;
+    type UnbondingPeriod = ConstU32<2>;
+    type MaxUnlockingChunks = ConstU32<4>;
+    type CandidacyBond = ConstU64<100>;
+    type CandidacyBondUnbondingPeriod = ConstU32<2>;
+    type MaxSupportersPerCandidate = ConstU32<3>;
+    type MaxCleanupPerBlock = MaxCleanup;
+    type EvictionCount = ConstU32<1>;
+    type CandidateGracePeriod = ConstU32<2>;
+    type MinimumCandidateVotes = ConstU64<200>;
+    type SessionHistoryDepth = ConstU32<2>;
+    type VoteDecayRate = DecayRate;
+    type ElectionStrategy = TestElection;
+    type ValidatorPerformanceProvider = TestPerformance;
+    type DemotionRule = ConsecutiveUnderperformance<ConstU32<5>, ConstU32<2>>;
+    type StandbyPeriod = ConstU32<2>;
+    type SharingPercentNoticePeriod = ConstU32<2>;
+    type MaxStandbyNodes = ConstU32<1>;
+    type MinimumSelfStake = MinSelfStake;
+    type AllowVotingInterestTransfers = AllowTransfers;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type OnCandidacyBondSlashed = ();
+    type AdminTransferDelay = ConstU64<2>;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: (1..=20).map(|account| (account, 10_000)).collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
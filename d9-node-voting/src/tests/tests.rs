@@ -0,0 +1,900 @@
+use super::mock::*;
+use crate::*;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{EnsureOrigin, Hooks, OnKilledAccount},
+    weights::Weight,
+    BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_session::SessionManager;
+
+const WHALE: AccountId = 1;
+const SMALL_VOTERS: [AccountId; 3] = [2, 3, 4];
+const WHALE_A: AccountId = 10;
+const WHALE_B: AccountId = 11;
+const MINORITY_C: AccountId = 12;
+const MINORITY_D: AccountId = 13;
+
+fn submit_candidacy(candidate: AccountId) {
+    assert_ok!(NodeVoting::submit_candidacy(
+        RawOrigin::Signed(candidate).into(),
+        NodeMetadataStruct {
+            sharing_percent: 50,
+            ..Default::default()
+        },
+    ));
+}
+
+fn delegate(voter: AccountId, delegations: Vec<(AccountId, u64)>) {
+    let total = delegations.iter().map(|(_, votes)| votes).sum();
+    NodeVoting::add_voting_interest_internal(voter, total);
+    assert_ok!(NodeVoting::delegate_votes(
+        RawOrigin::Signed(voter).into(),
+        delegations
+            .into_iter()
+            .map(|(candidate, votes)| ValidatorDelegations { candidate, votes })
+            .collect(),
+    ));
+}
+
+/// the whale puts 500 votes on each of two candidates, three small voters put 150 on each of
+/// two other candidates. the whale holds 1000 of 1900 votes.
+fn setup_whale_and_minority() {
+    for candidate in [WHALE_A, WHALE_B, MINORITY_C, MINORITY_D] {
+        submit_candidacy(candidate);
+    }
+    delegate(WHALE, vec![(WHALE_A, 500), (WHALE_B, 500)]);
+    for voter in SMALL_VOTERS {
+        delegate(voter, vec![(MINORITY_C, 150), (MINORITY_D, 150)]);
+    }
+}
+
+fn sorted(mut accounts: Vec<AccountId>) -> Vec<AccountId> {
+    accounts.sort();
+    accounts
+}
+
+#[test]
+fn top_votes_gives_every_seat_to_the_whale() {
+    new_test_ext().execute_with(|| {
+        UsePhragmen::set(false);
+        setup_whale_and_minority();
+        let validators = NodeVoting::new_session(1).unwrap();
+        assert_eq!(sorted(validators), vec![WHALE_A, WHALE_B]);
+    });
+}
+
+#[test]
+fn sequential_phragmen_gives_the_minority_a_seat() {
+    new_test_ext().execute_with(|| {
+        UsePhragmen::set(true);
+        setup_whale_and_minority();
+        let validators = NodeVoting::new_session(1).unwrap();
+        assert_eq!(validators.len(), 2);
+        let whale_seats = validators.iter().filter(|v| [WHALE_A, WHALE_B].contains(v)).count();
+        let minority_seats =
+            validators.iter().filter(|v| [MINORITY_C, MINORITY_D].contains(v)).count();
+        assert_eq!((whale_seats, minority_seats), (1, 1));
+    });
+}
+
+#[test]
+fn sequential_phragmen_only_counts_the_largest_voters() {
+    new_test_ext().execute_with(|| {
+        UsePhragmen::set(true);
+        MaxElectingVoters::set(1);
+        setup_whale_and_minority();
+        let validators = NodeVoting::new_session(1).unwrap();
+        assert_eq!(sorted(validators), vec![WHALE_A, WHALE_B]);
+    });
+}
+
+#[test]
+fn sequential_phragmen_fills_seats_without_support_by_votes() {
+    new_test_ext().execute_with(|| {
+        UsePhragmen::set(true);
+        submit_candidacy(WHALE_A);
+        submit_candidacy(WHALE_B);
+        submit_candidacy(MINORITY_C);
+        delegate(WHALE, vec![(MINORITY_C, 10)]);
+        let validators = NodeVoting::new_session(1).unwrap();
+        assert_eq!(validators.len(), 2);
+        assert_eq!(validators[0], MINORITY_C);
+    });
+}
+
+#[test]
+fn start_session_records_stats_of_the_elected_validators() {
+    new_test_ext().execute_with(|| {
+        UsePhragmen::set(true);
+        setup_whale_and_minority();
+        let validators = NodeVoting::new_session(1).unwrap();
+        NodeVoting::start_session(1);
+        for validator in validators {
+            assert!(CurrentValidatorVoteStats::<Test>::contains_key(validator));
+        }
+        assert!(ElectedValidators::<Test>::get(1).is_none());
+    });
+}
+
+#[test]
+fn sorted_candidates_follow_vote_changes() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        assert_ok!(NodeVoting::try_remove_votes_from_candidate(
+            RawOrigin::Signed(WHALE).into(),
+            WHALE_B,
+            400,
+        ));
+        assert_eq!(
+            NodeVoting::get_sorted_candidates_with_votes(),
+            vec![(WHALE_A, 500), (MINORITY_C, 450), (MINORITY_D, 450), (WHALE_B, 100)]
+        );
+        assert_ok!(NodeVoting::remove_candidacy(RawOrigin::Signed(WHALE_A).into()));
+        assert_eq!(
+            NodeVoting::get_sorted_candidates(),
+            Some(vec![MINORITY_C, MINORITY_D, WHALE_B])
+        );
+    });
+}
+
+#[test]
+fn a_full_candidate_list_keeps_the_candidates_with_most_votes() {
+    new_test_ext().execute_with(|| {
+        // more candidates than `MaxCandidates`, as genesis could set up
+        for candidate in 1..=10 {
+            NodeVoting::set_candidate_votes(&candidate, candidate * 10);
+        }
+
+        // outranks the last entry, which makes way
+        NodeVoting::set_candidate_votes(&11, 55);
+        let sorted = NodeVoting::get_sorted_candidates_with_votes();
+        assert_eq!(sorted.len(), 10);
+        assert_eq!(sorted[5], (11, 55));
+        assert_eq!(sorted.last(), Some(&(2, 20)));
+
+        // ranks below everyone, so the list is left as it is
+        NodeVoting::set_candidate_votes(&12, 5);
+        assert_eq!(NodeVoting::get_sorted_candidates_with_votes(), sorted);
+    });
+}
+
+#[test]
+fn candidates_accept_at_most_max_supporters() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::add_voting_interest_internal(5, 10);
+        assert_noop!(
+            NodeVoting::delegate_votes(
+                RawOrigin::Signed(5).into(),
+                vec![ValidatorDelegations { candidate: MINORITY_C, votes: 10 }],
+            ),
+            Error::<Test>::CandidateHasMaxSupporters
+        );
+        assert_ok!(NodeVoting::delegate_votes(
+            RawOrigin::Signed(5).into(),
+            vec![ValidatorDelegations { candidate: WHALE_A, votes: 10 }],
+        ));
+    });
+}
+
+#[test]
+fn removed_candidate_support_is_returned_over_several_blocks() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        assert_ok!(NodeVoting::remove_candidacy(RawOrigin::Signed(MINORITY_C).into()));
+        assert_eq!(NodeVoting::total_number_of_candidate_nodes(), 3);
+        assert!(NodeVoting::node_votes(MINORITY_C).is_none());
+
+        NodeVoting::on_idle(1, Weight::MAX);
+        assert_eq!(NodeVoting::candidate_cleanup_queue().into_inner(), vec![MINORITY_C]);
+        assert_eq!(NodeVoting::candidate_supporter_count(MINORITY_C), 1);
+        assert_noop!(
+            NodeVoting::submit_candidacy(
+                RawOrigin::Signed(MINORITY_C).into(),
+                NodeMetadataStruct {
+                    sharing_percent: 50,
+                    ..Default::default()
+                },
+            ),
+            Error::<Test>::CandidateCleanupPending
+        );
+
+        NodeVoting::on_idle(2, Weight::MAX);
+        assert!(NodeVoting::candidate_cleanup_queue().is_empty());
+        for voter in SMALL_VOTERS {
+            assert_eq!(NodeVoting::vote_tokens(voter).unwrap().delegated, 150);
+            assert_eq!(NodeVoting::user_to_node_votes((voter, MINORITY_C)), 0);
+        }
+        submit_candidacy(MINORITY_C);
+    });
+}
+
+#[test]
+#[should_panic(expected = "MaxCleanupPerBlock of zero")]
+fn cleanup_needs_a_positive_max_per_block() {
+    new_test_ext().execute_with(|| {
+        <NodeVoting as Hooks<u64>>::integrity_test();
+        MaxCleanup::set(0);
+        <NodeVoting as Hooks<u64>>::integrity_test();
+    });
+}
+
+#[test]
+fn candidates_below_the_floor_are_evicted_after_their_grace_period() {
+    new_test_ext().execute_with(|| {
+        const UNSUPPORTED: AccountId = 14;
+        setup_whale_and_minority();
+        submit_candidacy(UNSUPPORTED);
+        assert_eq!(Balances::reserved_balance(UNSUPPORTED), 100);
+
+        NodeVoting::new_session(1);
+        NodeVoting::start_session(1);
+        assert!(NodeVoting::node_votes(UNSUPPORTED).is_some());
+
+        NodeVoting::new_session(2);
+        NodeVoting::start_session(2);
+        assert!(NodeVoting::node_votes(UNSUPPORTED).is_none());
+        assert_eq!(NodeVoting::total_number_of_candidate_nodes(), 4);
+        assert_eq!(Balances::reserved_balance(UNSUPPORTED), 100);
+        System::assert_has_event(Event::<Test>::CandidateEvicted(UNSUPPORTED, 0).into());
+        System::assert_has_event(
+            Event::<Test>::CandidacyBondUnbonding(UNSUPPORTED, 100, 4).into(),
+        );
+
+        // everyone left is above the floor and the list is not full
+        NodeVoting::new_session(3);
+        NodeVoting::start_session(3);
+        assert_eq!(NodeVoting::total_number_of_candidate_nodes(), 4);
+    });
+}
+
+#[test]
+fn session_node_list_keeps_only_the_history_depth() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        for session_index in 1..=3 {
+            NodeVoting::new_session(session_index);
+        }
+        assert!(NodeVoting::session_node_list(1).is_none());
+        assert_eq!(NodeVoting::session_node_list(3).unwrap().len(), 4);
+        assert!(NodeVoting::session_node_list(2).is_some());
+    });
+}
+
+#[test]
+fn decayed_votes_are_restored_by_reaffirming() {
+    new_test_ext().execute_with(|| {
+        DecayRate::set(Perbill::from_percent(10));
+        setup_whale_and_minority();
+        CurrentSessionIndex::<Test>::put(2);
+        for voter in SMALL_VOTERS {
+            assert_ok!(NodeVoting::reaffirm_votes(RawOrigin::Signed(voter).into()));
+        }
+        assert_eq!(NodeVoting::pending_vote_decay(&WHALE), Perbill::from_percent(19));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+
+        // planning a session leaves the totals alone
+        NodeVoting::new_session(3);
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+
+        // on_idle settles a few voters per block until every voter is settled for the session
+        NodeVoting::on_idle(1, Weight::MAX);
+        assert!(NodeVoting::vote_decay_cursor().is_some());
+        for block in 2..=10 {
+            NodeVoting::on_idle(block, Weight::MAX);
+        }
+        assert!(NodeVoting::vote_decay_cursor().is_none());
+        assert_eq!(NodeVoting::vote_decay_swept_session(), Some(2));
+        let validators = NodeVoting::new_session(3).unwrap();
+        assert_eq!(sorted(validators), vec![MINORITY_C, MINORITY_D]);
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(405));
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(405));
+        assert_eq!(NodeVoting::node_votes(MINORITY_C), Some(450));
+        let ranking = NodeVoting::get_sorted_candidates().unwrap();
+        assert_eq!(sorted(ranking[..2].to_vec()), vec![MINORITY_C, MINORITY_D]);
+
+        // acting on their delegations reaffirms the voter
+        assert_ok!(NodeVoting::try_remove_votes_from_candidate(
+            RawOrigin::Signed(WHALE).into(),
+            WHALE_B,
+            100
+        ));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(400));
+
+        CurrentSessionIndex::<Test>::put(3);
+        assert_ok!(NodeVoting::settle_vote_decay(RawOrigin::Signed(5).into(), WHALE));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(450));
+        assert_ok!(NodeVoting::reaffirm_votes(RawOrigin::Signed(WHALE).into()));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(400));
+        assert_eq!(NodeVoting::applied_vote_decay(WHALE), Perbill::zero());
+    });
+}
+
+#[test]
+fn elections_count_vote_decay_not_yet_settled() {
+    new_test_ext().execute_with(|| {
+        DecayRate::set(Perbill::from_percent(10));
+        setup_whale_and_minority();
+        CurrentSessionIndex::<Test>::put(2);
+        for voter in SMALL_VOTERS {
+            assert_ok!(NodeVoting::reaffirm_votes(RawOrigin::Signed(voter).into()));
+        }
+        // no block had room for the sweep
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+        let ranking = NodeVoting::get_sorted_candidates_with_votes();
+        assert_eq!(
+            ranking.iter().find(|(node, _)| *node == WHALE_A),
+            Some(&(WHALE_A, 405))
+        );
+        assert_eq!(sorted(vec![ranking[0].0, ranking[1].0]), vec![MINORITY_C, MINORITY_D]);
+        let validators = NodeVoting::new_session(3).unwrap();
+        assert_eq!(sorted(validators), vec![MINORITY_C, MINORITY_D]);
+
+        // the whale's 810 remaining votes now trail the minority's 900 for the first seat
+        UsePhragmen::set(true);
+        let validators = NodeVoting::new_session(4).unwrap();
+        assert!([MINORITY_C, MINORITY_D].contains(&validators[0]));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+    });
+}
+
+#[test]
+fn chronically_offline_validators_are_put_on_standby() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::new_session(1);
+        NodeVoting::start_session(1);
+        OfflineValidators::set(vec![WHALE_A]);
+
+        NodeVoting::end_session(1);
+        NodeVoting::start_session(2);
+        assert!(NodeVoting::current_validator_vote_stats(WHALE_A).is_some());
+        NodeVoting::end_session(2);
+        System::assert_has_event(Event::<Test>::ValidatorDemoted(WHALE_A, 4).into());
+
+        let offline = NodeVoting::validator_performance(WHALE_A);
+        assert_eq!(offline.sessions_tracked, 2);
+        assert_eq!(offline.uptime(), Perbill::zero());
+        let online = NodeVoting::validator_performance(WHALE_B);
+        assert_eq!(online.blocks_produced, 20);
+        assert_eq!(online.uptime(), Perbill::one());
+
+        // still a candidate, but left out of elections while on standby
+        NodeVoting::start_session(3);
+        assert!(NodeVoting::current_validator_vote_stats(WHALE_A).is_none());
+        assert!(NodeVoting::current_validator_vote_stats(WHALE_B).is_some());
+        assert!(!NodeVoting::new_session(4).unwrap().contains(&WHALE_A));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+
+        CurrentSessionIndex::<Test>::put(5);
+        assert!(NodeVoting::new_session(6).unwrap().contains(&WHALE_A));
+        assert!(NodeVoting::demoted_until(WHALE_A).is_none());
+    });
+}
+
+#[test]
+fn frontend_queries_page_the_ranking_and_project_reward_shares() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        let page = NodeVoting::get_candidates_page(1, 2);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].votes, 500);
+        assert_eq!(page[1].votes, 450);
+        assert_eq!(page[1].metadata.as_ref().unwrap().sharing_percent, 50);
+        assert!(NodeVoting::get_candidates_page(4, 2).is_empty());
+
+        assert_eq!(
+            sorted(
+                NodeVoting::get_candidate_supporters(MINORITY_C)
+                    .into_iter()
+                    .map(|(supporter, _)| supporter)
+                    .collect()
+            ),
+            SMALL_VOTERS.to_vec()
+        );
+        NodeVoting::add_voting_interest_internal(WHALE, 50);
+        let whale = NodeVoting::get_voter_delegations(WHALE);
+        assert_eq!(whale.delegations.len(), 2);
+        assert_eq!((whale.total, whale.available), (1050, 50));
+
+        assert_eq!(
+            NodeVoting::projected_reward_share(WHALE, WHALE_A),
+            Some(Perquintill::from_percent(50))
+        );
+        assert_eq!(
+            NodeVoting::projected_reward_share(2, MINORITY_C),
+            Some(Perquintill::from_percent(50) * Perquintill::from_rational(1u64, 3u64))
+        );
+        assert_eq!(NodeVoting::projected_reward_share(2, WHALE_A), None);
+    });
+}
+
+#[test]
+fn validator_stats_split_self_stake_from_external_support() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::add_voting_interest_internal(WHALE_B, 300);
+        assert_noop!(
+            NodeVoting::declare_self_stake(RawOrigin::Signed(WHALE_B).into(), 301),
+            Error::<Test>::DelegatorHasInsufficientVotes
+        );
+        assert_ok!(NodeVoting::declare_self_stake(RawOrigin::Signed(WHALE_B).into(), 300));
+        assert_eq!(NodeVoting::self_stake(&WHALE_B), 300);
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(800));
+
+        let validators = NodeVoting::new_session(1).unwrap();
+        assert!(validators.contains(&WHALE_B));
+        NodeVoting::start_session(1);
+        let stats = NodeVoting::current_validator_vote_stats(WHALE_B).unwrap();
+        assert_eq!((stats.total_votes, stats.self_votes, stats.delegated_votes), (800, 300, 500));
+        let stats = NodeVoting::current_validator_vote_stats(WHALE_A).unwrap();
+        assert_eq!((stats.self_votes, stats.delegated_votes), (0, 500));
+
+        // lowering the self-stake returns the votes to the candidate's voting interest
+        assert_ok!(NodeVoting::declare_self_stake(RawOrigin::Signed(WHALE_B).into(), 100));
+        assert_eq!(NodeVoting::vote_tokens(WHALE_B).unwrap().delegated, 100);
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(600));
+    });
+}
+
+#[test]
+fn candidates_below_the_minimum_self_stake_are_not_elected() {
+    new_test_ext().execute_with(|| {
+        MinSelfStake::set(50);
+        setup_whale_and_minority();
+        assert!(NodeVoting::new_session(1).is_none());
+
+        NodeVoting::add_voting_interest_internal(MINORITY_D, 50);
+        assert_ok!(NodeVoting::declare_self_stake(RawOrigin::Signed(MINORITY_D).into(), 50));
+        assert_eq!(NodeVoting::new_session(2).unwrap(), vec![MINORITY_D]);
+    });
+}
+
+#[test]
+fn rebalance_replaces_the_whole_allocation() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        let voter = SMALL_VOTERS[0];
+        let target = |candidate, votes| ValidatorDelegations::<Test> { candidate, votes };
+        assert_noop!(
+            NodeVoting::rebalance_delegations(
+                RawOrigin::Signed(voter).into(),
+                vec![target(MINORITY_C, 200), target(WHALE_A, 101)]
+            ),
+            Error::<Test>::DelegatorHasInsufficientVotes
+        );
+        assert_noop!(
+            NodeVoting::rebalance_delegations(
+                RawOrigin::Signed(voter).into(),
+                vec![target(WHALE_A, 100), target(WHALE_A, 100)]
+            ),
+            Error::<Test>::DuplicateCandidateInDelegations
+        );
+
+        // moving votes out of a delegation frees them for another in the same call
+        assert_ok!(NodeVoting::rebalance_delegations(
+            RawOrigin::Signed(voter).into(),
+            vec![target(MINORITY_C, 100), target(WHALE_A, 200)]
+        ));
+        let mut changes = match System::events().pop().unwrap().event {
+            RuntimeEvent::NodeVoting(Event::DelegationsRebalanced(who, changes)) => {
+                assert_eq!(who, voter);
+                changes
+            }
+            other => panic!("unexpected event {:?}", other),
+        };
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![(WHALE_A, 0, 200), (MINORITY_C, 150, 100), (MINORITY_D, 150, 0)]
+        );
+        assert_eq!(NodeVoting::node_votes(MINORITY_C), Some(400));
+        assert_eq!(NodeVoting::node_votes(MINORITY_D), Some(300));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(700));
+        assert_eq!(NodeVoting::candidate_supporter_count(MINORITY_D), 2);
+        assert_eq!(NodeVoting::vote_tokens(voter).unwrap().delegated, 300);
+    });
+}
+
+#[test]
+fn sharing_percent_changes_apply_after_the_notice_period() {
+    new_test_ext().execute_with(|| {
+        submit_candidacy(WHALE_A);
+        assert_ok!(NodeVoting::change_candidate_supporter_share(
+            RawOrigin::Signed(WHALE_A).into(),
+            20
+        ));
+        System::assert_last_event(
+            Event::<Test>::SharingPercentChangeAnnounced(WHALE_A, 20, 2).into(),
+        );
+
+        NodeVoting::start_session(1);
+        assert_eq!(NodeVoting::get_node_sharing_percent(WHALE_A), Some(50));
+        NodeVoting::start_session(2);
+        let node_metadata = NodeVoting::node_metadata(WHALE_A).unwrap();
+        assert_eq!(node_metadata.sharing_percent, 20);
+        assert_eq!(node_metadata.index_of_last_percent_change, 2);
+        assert!(NodeVoting::pending_sharing_percent(WHALE_A).is_none());
+
+        // announcing the current percent cancels a pending change
+        assert_ok!(NodeVoting::change_candidate_supporter_share(
+            RawOrigin::Signed(WHALE_A).into(),
+            30
+        ));
+        assert_ok!(NodeVoting::change_candidate_supporter_share(
+            RawOrigin::Signed(WHALE_A).into(),
+            20
+        ));
+        System::assert_last_event(Event::<Test>::SharingPercentChangeCancelled(WHALE_A).into());
+        assert!(NodeVoting::pending_sharing_percent(WHALE_A).is_none());
+
+        let website: BoundedVec<u8, _> = b"https://node.example".to_vec().try_into().unwrap();
+        assert_ok!(NodeVoting::update_candidate_details(
+            RawOrigin::Signed(WHALE_A).into(),
+            website.clone(),
+            BoundedVec::default(),
+            b"eu-west".to_vec().try_into().unwrap(),
+            BoundedVec::default(),
+            Some(WHALE),
+        ));
+        let node_metadata = NodeVoting::node_metadata(WHALE_A).unwrap();
+        assert_eq!(node_metadata.website, website);
+        assert_eq!(node_metadata.identity, Some(WHALE));
+        assert_eq!(node_metadata.sharing_percent, 20);
+    });
+}
+
+#[test]
+fn metadata_migration_keeps_existing_entries() {
+    use codec::Encode;
+    use frame_support::traits::{ConstU32, OnRuntimeUpgrade, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        let name: BoundedVec<u8, ConstU32<128>> = b"node".to_vec().try_into().unwrap();
+        for (candidate, sharing_percent) in [(WHALE_A, 35u8), (WHALE_B, 60u8)] {
+            frame_support::storage::unhashed::put_raw(
+                &NodeMetadata::<Test>::hashed_key_for(candidate),
+                &(name.clone(), sharing_percent, 7u32).encode(),
+            );
+        }
+        StorageVersion::new(4).put::<NodeVoting>();
+
+        migration::MigrateToExtendedNodeMetadata::<Test>::on_runtime_upgrade();
+        let node_metadata = NodeVoting::node_metadata(WHALE_A).unwrap();
+        assert_eq!(node_metadata.name, name);
+        assert_eq!(node_metadata.sharing_percent, 35);
+        assert_eq!(node_metadata.index_of_last_percent_change, 7);
+        assert!(node_metadata.website.is_empty());
+        assert!(node_metadata.contact.is_empty());
+        assert!(node_metadata.region.is_empty());
+        assert!(node_metadata.telemetry_url.is_empty());
+        assert_eq!(node_metadata.identity, None);
+        assert_eq!(NodeVoting::node_metadata(WHALE_B).unwrap().sharing_percent, 60);
+        assert_eq!(StorageVersion::get::<NodeVoting>(), 5);
+
+        // running it again leaves the migrated entries alone
+        migration::MigrateToExtendedNodeMetadata::<Test>::on_runtime_upgrade();
+        assert_eq!(NodeVoting::node_metadata(WHALE_A), Some(node_metadata));
+    });
+}
+
+#[test]
+fn vote_managers_delegate_but_cannot_transfer() {
+    new_test_ext().execute_with(|| {
+        const CUSTODIAN: AccountId = 9;
+        setup_whale_and_minority();
+        NodeVoting::add_voting_interest_internal(WHALE, 100);
+        let delegation = vec![ValidatorDelegations::<Test> { candidate: WHALE_A, votes: 100 }];
+        assert_noop!(
+            NodeVoting::delegate_votes_for(
+                RawOrigin::Signed(CUSTODIAN).into(),
+                WHALE,
+                delegation.clone()
+            ),
+            Error::<Test>::NotVoteManager
+        );
+
+        assert_ok!(NodeVoting::set_vote_manager(RawOrigin::Signed(WHALE).into(), Some(CUSTODIAN)));
+        assert_ok!(NodeVoting::delegate_votes_for(
+            RawOrigin::Signed(CUSTODIAN).into(),
+            WHALE,
+            delegation
+        ));
+        assert_eq!(NodeVoting::user_to_node_votes((WHALE, WHALE_A)), 600);
+        assert_ok!(NodeVoting::rebalance_delegations_for(
+            RawOrigin::Signed(CUSTODIAN).into(),
+            WHALE,
+            vec![ValidatorDelegations::<Test> { candidate: WHALE_A, votes: 1100 }]
+        ));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(1100));
+        assert_eq!(NodeVoting::node_votes(WHALE_B), Some(0));
+
+        assert_ok!(NodeVoting::set_vote_manager(RawOrigin::Signed(WHALE).into(), None));
+        assert_noop!(
+            NodeVoting::rebalance_delegations_for(
+                RawOrigin::Signed(CUSTODIAN).into(),
+                WHALE,
+                Vec::new()
+            ),
+            Error::<Test>::NotVoteManager
+        );
+    });
+}
+
+#[test]
+fn only_undelegated_voting_interest_can_be_transferred() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::add_voting_interest_internal(WHALE, 100);
+        assert_noop!(
+            NodeVoting::transfer_voting_interest(RawOrigin::Signed(WHALE).into(), 5, 100),
+            Error::<Test>::VotingInterestTransfersDisabled
+        );
+        assert_noop!(
+            NodeVoting::force_transfer_voting_interest(RawOrigin::Root.into(), WHALE, 5, 101),
+            Error::<Test>::DelegatorHasInsufficientVotes
+        );
+        assert_ok!(NodeVoting::force_transfer_voting_interest(
+            RawOrigin::Root.into(),
+            WHALE,
+            5,
+            60
+        ));
+        System::assert_last_event(Event::<Test>::VotingInterestTransferred(WHALE, 5, 60).into());
+
+        AllowTransfers::set(true);
+        assert_ok!(NodeVoting::transfer_voting_interest(RawOrigin::Signed(WHALE).into(), 5, 40));
+        assert_eq!(NodeVoting::vote_tokens(WHALE).unwrap().total, 1000);
+        assert_eq!(NodeVoting::vote_tokens(5).unwrap().total, 100);
+    });
+}
+
+#[test]
+fn ended_sessions_rank_candidates_into_tiers() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::new_session(1);
+        NodeVoting::start_session(1);
+        NodeVoting::end_session(1);
+
+        let tiers = NodeVoting::node_tiers().into_inner();
+        assert_eq!(tiers.len(), 4);
+        assert_eq!(tiers[0].1, NodeTier::Super(SuperNodeSubTier::Upper));
+        assert_eq!(tiers[1].1, NodeTier::Super(SuperNodeSubTier::Middle));
+        assert_eq!(tiers[2].1, NodeTier::StandBy);
+        assert_eq!(tiers[3].1, NodeTier::Candidate);
+        assert_eq!(sorted(vec![tiers[0].0, tiers[1].0]), vec![WHALE_A, WHALE_B]);
+        assert_eq!(NodeVoting::node_tier(&tiers[2].0), Some(NodeTier::StandBy));
+    });
+}
+
+#[test]
+fn pallet_admin_handover_waits_for_acceptance() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            NodeVoting::set_pallet_admin(RawOrigin::Signed(WHALE).into(), 5),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Root.into(), WHALE));
+        System::assert_last_event(Event::<Test>::AdminChanged(None, WHALE).into());
+
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Signed(WHALE).into(), 5));
+        System::assert_last_event(Event::<Test>::AdminProposed(5, 3).into());
+        assert_eq!(NodeVoting::pallet_admin(), Some(WHALE));
+        assert_noop!(
+            NodeVoting::accept_pallet_admin(RawOrigin::Signed(6).into()),
+            Error::<Test>::NotPendingAdmin
+        );
+        assert_noop!(
+            NodeVoting::accept_pallet_admin(RawOrigin::Signed(5).into()),
+            Error::<Test>::AdminTimelockActive
+        );
+
+        System::set_block_number(3);
+        assert_ok!(NodeVoting::accept_pallet_admin(RawOrigin::Signed(5).into()));
+        System::assert_last_event(Event::<Test>::AdminChanged(Some(WHALE), 5).into());
+        assert_eq!(NodeVoting::pallet_admin(), Some(5));
+        assert_eq!(NodeVoting::pending_pallet_admin(), None);
+        assert_noop!(
+            NodeVoting::accept_pallet_admin(RawOrigin::Signed(5).into()),
+            Error::<Test>::NoPendingAdmin
+        );
+    });
+}
+
+#[test]
+fn root_overrides_a_pending_admin_handover() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Root.into(), WHALE));
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Signed(WHALE).into(), 5));
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Root.into(), 6));
+        System::assert_last_event(Event::<Test>::AdminChanged(Some(WHALE), 6).into());
+        assert_eq!(NodeVoting::pending_pallet_admin(), None);
+
+        assert_ok!(NodeVoting::set_pallet_admin(RawOrigin::Signed(6).into(), 5));
+        assert_noop!(
+            NodeVoting::cancel_pallet_admin(RawOrigin::Signed(5).into()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(NodeVoting::cancel_pallet_admin(RawOrigin::Signed(6).into()));
+        System::assert_last_event(Event::<Test>::AdminProposalCancelled(5).into());
+        assert_eq!(NodeVoting::pending_pallet_admin(), None);
+        assert_noop!(
+            NodeVoting::cancel_pallet_admin(RawOrigin::Root.into()),
+            Error::<Test>::NoPendingAdmin
+        );
+
+        assert_eq!(
+            EnsurePalletAdmin::<Test>::try_origin(RuntimeOrigin::signed(6)).ok(),
+            Some(Some(6))
+        );
+        assert_eq!(EnsurePalletAdmin::<Test>::try_origin(RuntimeOrigin::root()).ok(), Some(None));
+        assert!(EnsurePalletAdmin::<Test>::try_origin(RuntimeOrigin::signed(WHALE)).is_err());
+    });
+}
+
+#[test]
+fn reaped_voters_lose_their_delegations() {
+    new_test_ext().execute_with(|| {
+        setup_whale_and_minority();
+        NodeVoting::on_killed_account(&WHALE);
+        assert!(NodeVoting::vote_tokens(WHALE).is_none());
+        assert_eq!(NodeVoting::user_to_node_votes((WHALE, WHALE_A)), 0);
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(0));
+        assert_eq!(NodeVoting::node_votes(MINORITY_C), Some(450));
+        System::assert_last_event(Event::<Test>::VotingInterestPruned(WHALE).into());
+    });
+}
+
+#[test]
+fn locked_voting_interest_unbonds_before_it_can_be_relocked() {
+    new_test_ext().execute_with(|| {
+        const VOTER: AccountId = 5;
+        assert_noop!(
+            NodeVoting::lock_voting_interest(RawOrigin::Signed(VOTER).into(), 1_000),
+            Error::<Test>::WrongVotingInterestMode
+        );
+        Mode::set(VotingInterestMode::Lock);
+        submit_candidacy(WHALE_A);
+        assert_noop!(
+            NodeVoting::lock_voting_interest(RawOrigin::Signed(VOTER).into(), 10_001),
+            Error::<Test>::InsufficientFreeBalance
+        );
+        assert_ok!(NodeVoting::lock_voting_interest(RawOrigin::Signed(VOTER).into(), 1_000));
+        assert_eq!(NodeVoting::vote_tokens(VOTER).unwrap().total, 1_000);
+        assert_eq!(Balances::locks(VOTER)[0].amount, 1_000);
+        assert_ok!(NodeVoting::delegate_votes(
+            RawOrigin::Signed(VOTER).into(),
+            vec![ValidatorDelegations { candidate: WHALE_A, votes: 600 }],
+        ));
+
+        // the 300 votes left cannot back all 600 delegated, so the delegation shrinks
+        assert_ok!(NodeVoting::unlock_voting_interest(RawOrigin::Signed(VOTER).into(), 700));
+        System::assert_last_event(
+            Event::<Test>::VotingInterestUnbonding(VOTER, 700, 700, 2).into(),
+        );
+        assert_eq!(NodeVoting::vote_tokens(VOTER).unwrap().delegated, 300);
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(300));
+        assert_eq!(Balances::locks(VOTER)[0].amount, 1_000);
+        assert_noop!(
+            NodeVoting::withdraw_unlocked_voting_tokens(RawOrigin::Signed(VOTER).into()),
+            Error::<Test>::NothingToWithdraw
+        );
+
+        NodeVoting::start_session(2);
+        assert_ok!(NodeVoting::withdraw_unlocked_voting_tokens(RawOrigin::Signed(VOTER).into()));
+        assert_eq!(Balances::locks(VOTER)[0].amount, 300);
+
+        assert_ok!(NodeVoting::lock_voting_interest(RawOrigin::Signed(VOTER).into(), 200));
+        assert_ok!(NodeVoting::delegate_votes(
+            RawOrigin::Signed(VOTER).into(),
+            vec![ValidatorDelegations { candidate: WHALE_A, votes: 200 }],
+        ));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(500));
+        assert_eq!(Balances::locks(VOTER)[0].amount, 500);
+
+        assert_ok!(NodeVoting::unlock_voting_interest(RawOrigin::Signed(VOTER).into(), 500));
+        assert_eq!(NodeVoting::node_votes(WHALE_A), Some(0));
+        NodeVoting::start_session(4);
+        assert_ok!(NodeVoting::withdraw_unlocked_voting_tokens(RawOrigin::Signed(VOTER).into()));
+        assert!(Balances::locks(VOTER).is_empty());
+    });
+}
+
+#[test]
+fn native_burn_grants_voting_interest_and_lowers_issuance() {
+    new_test_ext().execute_with(|| {
+        let issuance = Balances::total_issuance();
+        assert_noop!(
+            NodeVoting::add_voting_interest(RawOrigin::Signed(2).into(), 3, 99),
+            Error::<Test>::BurnAmountMustBeGreaterThan100
+        );
+        assert_noop!(
+            NodeVoting::add_voting_interest(RawOrigin::Signed(4).into(), 4, 10_000),
+            pallet_balances::Error::<Test>::Expendability
+        );
+
+        assert_ok!(NodeVoting::add_voting_interest(RawOrigin::Signed(2).into(), 3, 1_000));
+        System::assert_last_event(Event::<Test>::VotingInterestBought(2, 3, 1_000, 1_000).into());
+        assert_eq!(Balances::free_balance(2), 9_000);
+        assert_eq!(Balances::total_issuance(), issuance - 1_000);
+        assert_eq!(NodeVoting::burned_for_voting_interest(2), 1_000);
+        assert_eq!(NodeVoting::total_burned_for_voting_interest(), 1_000);
+        assert_eq!(NodeVoting::vote_tokens(3).unwrap().total, 1_000);
+        assert!(NodeVoting::vote_tokens(2).is_none());
+
+        assert_ok!(NodeVoting::add_voting_interest(RawOrigin::Signed(2).into(), 2, 500));
+        assert_eq!(NodeVoting::burned_for_voting_interest(2), 1_500);
+        assert_eq!(NodeVoting::total_burned_for_voting_interest(), 1_500);
+        assert_eq!(Balances::total_issuance(), issuance - 1_500);
+    });
+}
+
+#[test]
+fn removed_candidates_keep_their_bond_reserved_while_unbonding() {
+    new_test_ext().execute_with(|| {
+        const CANDIDATE: AccountId = 14;
+        submit_candidacy(CANDIDATE);
+        System::assert_has_event(Event::<Test>::CandidacyBondReserved(CANDIDATE, 100).into());
+        assert_eq!(NodeVoting::candidacy_bond(CANDIDATE), Some(100));
+        assert_eq!(Balances::reserved_balance(CANDIDATE), 100);
+
+        assert_ok!(NodeVoting::remove_candidacy(RawOrigin::Signed(CANDIDATE).into()));
+        assert_eq!(NodeVoting::candidacy_bond(CANDIDATE), None);
+        assert_eq!(Balances::reserved_balance(CANDIDATE), 100);
+        assert_noop!(
+            NodeVoting::withdraw_candidacy_bond(RawOrigin::Signed(CANDIDATE).into()),
+            Error::<Test>::CandidacyBondStillUnbonding
+        );
+
+        NodeVoting::start_session(2);
+        assert_ok!(NodeVoting::withdraw_candidacy_bond(RawOrigin::Signed(CANDIDATE).into()));
+        System::assert_last_event(Event::<Test>::CandidacyBondReturned(CANDIDATE, 100).into());
+        assert_eq!(Balances::reserved_balance(CANDIDATE), 0);
+        assert_eq!(Balances::free_balance(CANDIDATE), 10_000);
+        assert_noop!(
+            NodeVoting::withdraw_candidacy_bond(RawOrigin::Signed(CANDIDATE).into()),
+            Error::<Test>::NothingToWithdraw
+        );
+    });
+}
+
+#[test]
+fn bonds_can_be_slashed_before_and_after_removal() {
+    new_test_ext().execute_with(|| {
+        const SLASHED: AccountId = 14;
+        const DEREGISTERED: AccountId = 15;
+        submit_candidacy(SLASHED);
+        submit_candidacy(DEREGISTERED);
+        let issuance = Balances::total_issuance();
+        assert_noop!(
+            NodeVoting::slash_candidacy_bond(RawOrigin::Signed(1).into(), SLASHED),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        assert_ok!(NodeVoting::slash_candidacy_bond(RawOrigin::Root.into(), SLASHED));
+        System::assert_has_event(Event::<Test>::CandidacyBondSlashed(SLASHED, 100).into());
+        System::assert_last_event(Event::<Test>::CandidacyRemoved(SLASHED).into());
+        assert!(NodeVoting::node_votes(SLASHED).is_none());
+        assert_eq!(Balances::reserved_balance(SLASHED), 0);
+        assert_eq!(Balances::free_balance(SLASHED), 9_900);
+
+        // deregistering does not put the bond out of reach
+        assert_ok!(NodeVoting::remove_candidacy(RawOrigin::Signed(DEREGISTERED).into()));
+        assert_ok!(NodeVoting::slash_candidacy_bond(RawOrigin::Root.into(), DEREGISTERED));
+        System::assert_last_event(
+            Event::<Test>::CandidacyBondSlashed(DEREGISTERED, 100).into(),
+        );
+        assert_eq!(Balances::reserved_balance(DEREGISTERED), 0);
+        assert_eq!(NodeVoting::unbonding_candidacy_bond(DEREGISTERED), None);
+        assert_eq!(Balances::total_issuance(), issuance - 200);
+        assert_noop!(
+            NodeVoting::slash_candidacy_bond(RawOrigin::Root.into(), DEREGISTERED),
+            Error::<Test>::CandidateDoesNotExist
+        );
+    });
+}
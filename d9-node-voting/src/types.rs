@@ -4,7 +4,7 @@ use crate::BalanceOf;
 use codec::MaxEncodedLen;
 use frame_support::RuntimeDebugNoBound;
 use frame_support::{inherent::Vec, pallet_prelude::*, BoundedVec};
-use sp_runtime::traits::Convert;
+use sp_runtime::{traits::Convert, Perbill};
 use sp_staking::SessionIndex;
 
 #[derive(
@@ -96,12 +96,37 @@ pub struct ValidatorVoteStats<T: Config> {
 }
 
 #[derive(
-    PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Default,
+    Encode,
+    Decode,
+    RuntimeDebug,
+    TypeInfo,
+    MaxEncodedLen,
 )]
-pub struct NodeMetadataStruct {
+pub struct NodeMetadataStruct<AccountId> {
     pub name: BoundedVec<u8, ConstU32<128>>,
     pub sharing_percent: u8,
     pub index_of_last_percent_change: u32,
+    pub website: BoundedVec<u8, ConstU32<128>>,
+    pub contact: BoundedVec<u8, ConstU32<128>>,
+    /// geographic region the node runs in
+    pub region: BoundedVec<u8, ConstU32<64>>,
+    pub telemetry_url: BoundedVec<u8, ConstU32<128>>,
+    /// account whose on-chain identity describes the node operator
+    pub identity: Option<AccountId>,
+}
+
+/// a `sharing_percent` change announced by a candidate
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SharingPercentChange {
+    pub sharing_percent: u8,
+    /// session from whose start the change applies
+    pub effective_session: SessionIndex,
 }
 
 pub struct ValidatorStatsOf<T: Config>(PhantomData<T>);
@@ -112,10 +137,107 @@ impl<T: Config> Convert<T::AccountId, Option<ValidatorVoteStats<T>>> for Validat
 }
 
 pub trait NodeRewardManager<AccountId> {
-    fn update_rewards(end_index: SessionIndex, nodes_with_votes: Vec<(AccountId, u64)>) -> ();
+    fn update_rewards(
+        end_index: SessionIndex,
+        nodes_with_votes: Vec<(AccountId, u64, NodeTier)>,
+    ) -> ();
 }
 
 pub trait ReferendumManager {
     fn start_pending_votes(session_index: SessionIndex);
     fn end_active_votes(session_index: SessionIndex);
 }
+
+impl<AccountId> NodeRewardManager<AccountId> for () {
+    fn update_rewards(_: SessionIndex, _: Vec<(AccountId, u64, NodeTier)>) {}
+}
+
+impl ReferendumManager for () {
+    fn start_pending_votes(_: SessionIndex) {}
+    fn end_active_votes(_: SessionIndex) {}
+}
+
+/// how users acquire voting interest
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum VotingInterestMode {
+    /// tokens are burned through `add_voting_interest`
+    Burn,
+    /// tokens are locked through `lock_voting_interest` and can be unlocked again
+    Lock,
+}
+
+/// tokens waiting out an unbonding period
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct UnlockChunk<Balance> {
+    pub value: Balance,
+    pub unlock_session: SessionIndex,
+}
+
+/// how a validator performed in one session
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SessionPerformance {
+    /// whether the validator was seen online, e.g. through im-online heartbeats
+    pub online: bool,
+    pub blocks_produced: u32,
+}
+
+/// performance of a validator over all sessions it validated
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ValidatorPerformanceStats {
+    /// sessions for which performance data was reported
+    pub sessions_tracked: u32,
+    pub sessions_online: u32,
+    pub blocks_produced: u64,
+    /// underperforming sessions in a row, reset on demotion
+    pub consecutive_underperforming: u32,
+}
+
+impl ValidatorPerformanceStats {
+    /// share of tracked sessions in which the validator was online
+    pub fn uptime(&self) -> Perbill {
+        if self.sessions_tracked == 0 {
+            return Perbill::one();
+        }
+        Perbill::from_rational(self.sessions_online, self.sessions_tracked)
+    }
+}
+
+/// a candidate as shown in a page of the ranking
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct CandidateInfo<AccountId> {
+    pub account_id: AccountId,
+    pub votes: u64,
+    pub metadata: Option<NodeMetadataStruct<AccountId>>,
+}
+
+/// where a voter's votes went
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct VoterDelegations<AccountId> {
+    /// (candidate, votes delegated to it)
+    pub delegations: Vec<(AccountId, u64)>,
+    pub total: u64,
+    /// votes not yet delegated
+    pub available: u64,
+}
+
+/// standing of a candidate in a session, from the ranking at its end
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub enum NodeTier {
+    /// validated the session
+    Super(SuperNodeSubTier),
+    /// among the best ranked candidates that did not validate, next in line for a seat
+    StandBy,
+    Candidate,
+}
+
+/// third of the validators, by votes, a validator ranked in
+#[derive(
+    PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub enum SuperNodeSubTier {
+    Upper,
+    Middle,
+    Lower,
+}
@@ -1,11 +1,13 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
+#[cfg(test)]
+mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     // pallet_prelude imports resource such s storage, hooks dispatchResult, etc
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, traits::OnKilledAccount};
     use frame_system::pallet_prelude::*;
     use sp_std::vec::Vec;
     #[pallet::config]
@@ -14,6 +16,8 @@ pub mod pallet {
             IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type MaxReferralDepth: Get<u32>;
         type SetMaxReferralDepthOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+        /// whether a reaped account loses its link to its parent (`true`) or keeps it (`false`)
+        type PruneReapedAccounts: Get<bool>;
     }
 
     /// The current storage version.
@@ -30,6 +34,8 @@ pub mod pallet {
         NewReferralRelationshipCreated(T::AccountId, T::AccountId),
         NewReferralDepthSet(u32),
         NewDefaultParentSet(T::AccountId),
+        /// (parent, child) relationship removed because the child was reaped
+        ReferralRelationshipPruned(T::AccountId, T::AccountId),
     }
 
     #[pallet::error]
@@ -165,4 +171,25 @@ pub mod pallet {
             DirectReferralsCount::<T, I>::get(account_id)
         }
     }
+
+    /// called when `account` is reaped
+    ///
+    /// if `PruneReapedAccounts` is set the account's link to its parent is removed and the
+    /// parent's direct referral count decremented. links from the account's own children are
+    /// kept so their ancestry stays intact.
+    impl<T: Config<I>, I: 'static> OnKilledAccount<T::AccountId> for Pallet<T, I> {
+        fn on_killed_account(account: &T::AccountId) {
+            if !T::PruneReapedAccounts::get() {
+                return;
+            }
+            if let Some(parent) = <ReferralRelationships<T, I>>::take(account) {
+                <DirectReferralsCount<T, I>>::mutate(parent.clone(), |count| {
+                    *count = count.saturating_sub(1);
+                });
+                Self::deposit_event(
+                    Event::ReferralRelationshipPruned(parent, account.clone())
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,122 @@
+use crate as pallet_d9_referral;
+use crate::*;
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{ConstU32, ConstU64, Nothing},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+pub type AccountId = u64;
+type Block = frame_system::mocking::MockBlock<Test>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+
+construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_d9_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Referral: pallet_d9_referral::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_d9_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+/// hands referral bookkeeping from d9-balances to this pallet
+pub struct BalancesReferrals;
+impl pallet_d9_balances::ReferralManager<Test, ()> for BalancesReferrals {
+    fn get_parent(account: &AccountId) -> Option<AccountId> {
+        Referral::get_parent(account)
+    }
+
+    fn create_referral_relationship(parent: &AccountId, child: &AccountId) {
+        Referral::create_referral_relationship(parent, child)
+    }
+}
+
+impl pallet_d9_balances::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type Balance = u64;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<10>;
+    type AccountStore = System;
+    type ReserveIdentifier = [u8; 8];
+    type HoldIdentifier = ();
+    type FreezeIdentifier = ();
+    type MaxLocks = ConstU32<10>;
+    type MaxReserves = ConstU32<2>;
+    type MaxHolds = ConstU32<2>;
+    type MaxFreezes = ConstU32<2>;
+    type ReferralManager = BalancesReferrals;
+    type ReferralPolicy = pallet_d9_balances::ParentIsEndowingSender;
+    type OnAccountReaped = Referral;
+    type AccountFreezingLocks = Nothing;
+    type TransferFilter = ();
+    type TransferListOrigin = EnsureRoot<AccountId>;
+    type TransferLimitIncreaseDelay = ConstU64<10>;
+    type TransferLimitBuckets = ConstU32<5>;
+}
+
+parameter_types! {
+    pub static PruneReaped: bool = true;
+}
+
+impl pallet_d9_referral::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type MaxReferralDepth = ConstU32<5>;
+    type SetMaxReferralDepthOrigin = EnsureRoot<AccountId>;
+    type PruneReapedAccounts = PruneReaped;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    pallet_d9_balances::GenesisConfig::<Test> {
+        balances: vec![(1, 10_000)],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    pallet_d9_referral::GenesisConfig::<Test> {
+        max_referral_depth: 5,
+        phantom: Default::default(),
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
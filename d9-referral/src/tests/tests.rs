@@ -0,0 +1,60 @@
+use super::mock::*;
+use crate::*;
+use frame_support::assert_ok;
+
+/// 1 endows 2, which endows 3
+fn build_chain() {
+    assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 2, 1_000));
+    assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(2), 3, 100));
+    assert_eq!(Referral::get_ancestors(3), Some(vec![2, 1]));
+    assert_eq!(Referral::get_direct_referral_count(1), 1);
+    assert_eq!(Referral::get_direct_referral_count(2), 1);
+}
+
+#[test]
+fn reaped_accounts_lose_their_parent() {
+    new_test_ext().execute_with(|| {
+        build_chain();
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(2), 1, false));
+        assert!(!System::account_exists(&2));
+
+        assert_eq!(Referral::get_parent(2), None);
+        assert_eq!(Referral::get_direct_referral_count(1), 0);
+        System::assert_has_event(RuntimeEvent::Referral(Event::ReferralRelationshipPruned(1, 2)));
+        // the reaped account's own children keep their link to it
+        assert_eq!(Referral::get_parent(3), Some(2));
+        assert_eq!(Referral::get_ancestors(3), Some(vec![2]));
+        assert_eq!(Referral::get_direct_referral_count(2), 1);
+    });
+}
+
+#[test]
+fn reaped_accounts_keep_their_parent_unless_pruning() {
+    new_test_ext().execute_with(|| {
+        PruneReaped::set(false);
+        build_chain();
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(2), 1, false));
+        assert!(!System::account_exists(&2));
+
+        assert_eq!(Referral::get_parent(2), Some(1));
+        assert_eq!(Referral::get_direct_referral_count(1), 1);
+        assert_eq!(Referral::get_ancestors(3), Some(vec![2, 1]));
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            RuntimeEvent::Referral(Event::ReferralRelationshipPruned(..))
+        )));
+    });
+}
+
+#[test]
+fn an_account_endowed_again_gets_a_new_parent() {
+    new_test_ext().execute_with(|| {
+        build_chain();
+        assert_ok!(Balances::transfer_all(RuntimeOrigin::signed(3), 1, false));
+        assert_eq!(Referral::get_direct_referral_count(2), 0);
+
+        assert_ok!(Balances::transfer_allow_death(RuntimeOrigin::signed(1), 3, 100));
+        assert_eq!(Referral::get_ancestors(3), Some(vec![1]));
+        assert_eq!(Referral::get_direct_referral_count(1), 2);
+    });
+}